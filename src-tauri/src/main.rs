@@ -1,29 +1,189 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs;
 use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 use std::time::Duration;
-use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::{Manager, State, Window, WindowUrl};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use fs2::FileExt;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC};
+use reqwest::header::{HeaderName, HeaderValue};
+use rustls::client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier};
+use rustls::{Certificate, OwnedTrustAnchor, RootCertStore, ServerName};
+use sha2::{Digest, Sha256};
+use tauri::{
+    AppHandle, ClipboardManager, CustomMenuItem, LogicalSize, Manager, PhysicalPosition, State,
+    SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem, Window, WindowEvent,
+    WindowUrl,
+};
 use url::Url;
 
 const DEFAULT_TITLE: &str = "CRA";
+const DEFAULT_OFFLINE_TITLE_SUFFIX: &str = "(offline)";
 const DEFAULT_WIDTH: f64 = 1280.0;
 const DEFAULT_HEIGHT: f64 = 800.0;
 const DEFAULT_APP_URL: &str = "http://192.168.50.55:3000";
 const DEFAULT_ALLOWED_HOSTS: &str = "192.168.50.55";
+const DEFAULT_REACHABILITY_TIMEOUT_SECS: u64 = 8;
+const DEFAULT_REACHABILITY_CONNECT_TIMEOUT_SECS: u64 = 4;
+const DEFAULT_REACHABILITY_MAX_REDIRECTS: usize = 5;
+const DEFAULT_BOOTSTRAP_MAX_SECS: u64 = 30;
+const MIN_REACHABILITY_TIMEOUT_SECS: u64 = 1;
+const MAX_REACHABILITY_TIMEOUT_SECS: u64 = 120;
+const DEFAULT_RETRY_ATTEMPTS: u64 = 3;
+const MAX_RETRY_ATTEMPTS: u64 = 10;
+const RETRY_INITIAL_BACKOFF_MS: u64 = 500;
+const MAX_STARTUP_GRACE_SECS: u64 = 300;
+const STARTUP_GRACE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const REACHABILITY_RESULT_TTL: Duration = Duration::from_secs(1);
+/// Extra requests one reachability check makes for a 502/503/504 when
+/// `RETRY_ON_GATEWAY_ERRORS` is on, and the pause before each.
+const GATEWAY_RETRY_ATTEMPTS: u32 = 2;
+const GATEWAY_RETRY_DELAY: Duration = Duration::from_millis(500);
+const DEFAULT_LOG_MAX_SIZE_MB: u64 = 5;
+const LOG_ROTATION_GENERATIONS: u32 = 3;
+const MIN_RESTORED_WIDTH: f64 = 400.0;
+const MIN_RESTORED_HEIGHT: f64 = 300.0;
+// Stand-in for the missing axis when only one max bound is configured.
+const UNBOUNDED_WINDOW_DIMENSION: f64 = 65_535.0;
+const DEFAULT_WINDOW_ZOOM: f64 = 1.0;
+const DEFAULT_SERVER_INFO_PATH: &str = "/version";
+/// Longest non-JSON `get_server_info` body returned to the frontend.
+const SERVER_INFO_MAX_TEXT_CHARS: usize = 4096;
+const MIN_WINDOW_ZOOM: f64 = 0.5;
+const MAX_WINDOW_ZOOM: f64 = 3.0;
+const WINDOW_ZOOM_STEP: f64 = 0.1;
+const RETRY_PROGRESS_EVENT: &str = "retry-progress";
+const REACHABILITY_CHANGED_EVENT: &str = "reachability-changed";
+const SINGLE_INSTANCE_FOCUS_MESSAGE: &[u8] = b"cra-client:focus\n";
+const SINGLE_INSTANCE_ACK: &[u8] = b"cra-client:ok\n";
+const SINGLE_INSTANCE_TIMEOUT: Duration = Duration::from_millis(500);
+// Portion of the window that must overlap a monitor for a saved position to be reused.
+const MIN_VISIBLE_WINDOW_EDGE: i32 = 100;
 const ENV_APP_URL: &str = "CRA_CLIENT_APP_URL";
 const ENV_ALLOWED_HOSTS: &str = "CRA_CLIENT_ALLOWED_HOSTS";
 const ENV_WINDOW_TITLE: &str = "CRA_CLIENT_WINDOW_TITLE";
 const ENV_WINDOW_WIDTH: &str = "CRA_CLIENT_WINDOW_WIDTH";
 const ENV_WINDOW_HEIGHT: &str = "CRA_CLIENT_WINDOW_HEIGHT";
+const ENV_WINDOW_ICON_PATH: &str = "CRA_CLIENT_WINDOW_ICON_PATH";
+const ENV_WINDOW_MIN_WIDTH: &str = "CRA_CLIENT_WINDOW_MIN_WIDTH";
+const ENV_WINDOW_MIN_HEIGHT: &str = "CRA_CLIENT_WINDOW_MIN_HEIGHT";
+const ENV_WINDOW_MAX_WIDTH: &str = "CRA_CLIENT_WINDOW_MAX_WIDTH";
+const ENV_WINDOW_MAX_HEIGHT: &str = "CRA_CLIENT_WINDOW_MAX_HEIGHT";
+const ENV_WINDOW_SIZE_UNIT: &str = "CRA_CLIENT_WINDOW_SIZE_UNIT";
 const ENV_ALLOW_LOCALHOST_RELEASE: &str = "CRA_CLIENT_ALLOW_LOCALHOST_RELEASE";
 const ENV_MIN_WEB_BUILD_HASH: &str = "CRA_CLIENT_MIN_WEB_BUILD_HASH";
 const ENV_ENFORCE_WEB_BUILD: &str = "CRA_CLIENT_ENFORCE_WEB_BUILD";
+const ENV_REACHABILITY_TIMEOUT_SECS: &str = "CRA_CLIENT_REACHABILITY_TIMEOUT_SECS";
+const ENV_REACHABILITY_CONNECT_TIMEOUT_SECS: &str = "CRA_CLIENT_REACHABILITY_CONNECT_TIMEOUT_SECS";
+const ENV_REACHABILITY_MAX_REDIRECTS: &str = "CRA_CLIENT_REACHABILITY_MAX_REDIRECTS";
+const ENV_RETRY_ATTEMPTS: &str = "CRA_CLIENT_RETRY_ATTEMPTS";
+const ENV_STARTUP_GRACE_SECS: &str = "CRA_CLIENT_STARTUP_GRACE_SECS";
+const ENV_HEALTH_CHECK_PATH: &str = "CRA_CLIENT_HEALTH_CHECK_PATH";
+const ENV_SERVER_INFO_PATH: &str = "CRA_CLIENT_SERVER_INFO_PATH";
+const ENV_LOG_MAX_SIZE_MB: &str = "CRA_CLIENT_LOG_MAX_SIZE_MB";
+const ENV_PROFILE: &str = "CRA_CLIENT_PROFILE";
+const ENV_DATA_DIR: &str = "CRA_CLIENT_DATA_DIR";
+const ENV_PINNED_CERT_SHA256: &str = "CRA_CLIENT_PINNED_CERT_SHA256";
+const ENV_CLIENT_CERT_PATH: &str = "CRA_CLIENT_CLIENT_CERT_PATH";
+const ENV_CLIENT_KEY_PATH: &str = "CRA_CLIENT_CLIENT_KEY_PATH";
+const ENV_CLIENT_IDENTITY_PATH: &str = "CRA_CLIENT_CLIENT_IDENTITY_PATH";
+const ENV_CLIENT_IDENTITY_PASSWORD: &str = "CRA_CLIENT_CLIENT_IDENTITY_PASSWORD";
+const ENV_REACHABILITY_AUTH_HEADER: &str = "CRA_CLIENT_REACHABILITY_AUTH_HEADER";
+const ENV_WEBVIEW_HEADERS: &str = "CRA_CLIENT_WEBVIEW_HEADERS";
+const ENV_HTTP_PROXY_URL: &str = "CRA_CLIENT_HTTP_PROXY_URL";
+const ENV_UPDATE_MANIFEST_URL: &str = "CRA_CLIENT_UPDATE_MANIFEST_URL";
+const ENV_MINIMIZE_TO_TRAY: &str = "CRA_CLIENT_MINIMIZE_TO_TRAY";
+const ENV_PRECHECK_ALLOWED_HOSTS: &str = "CRA_CLIENT_PRECHECK_ALLOWED_HOSTS";
+const ENV_LOG_FORMAT: &str = "CRA_CLIENT_LOG_FORMAT";
+const ENV_EXTERNAL_SCHEMES: &str = "CRA_CLIENT_EXTERNAL_SCHEMES";
+const ENV_ENABLE_DEVTOOLS: &str = "CRA_CLIENT_ENABLE_DEVTOOLS";
+const ENV_KIOSK_MODE: &str = "CRA_CLIENT_KIOSK_MODE";
+const ENV_DISABLE_CONTEXT_MENU: &str = "CRA_CLIENT_DISABLE_CONTEXT_MENU";
+const ENV_DISABLE_TEXT_SELECTION: &str = "CRA_CLIENT_DISABLE_TEXT_SELECTION";
+const ENV_AUDIT_NAVIGATION: &str = "CRA_CLIENT_AUDIT_NAVIGATION";
+const ENV_WINDOW_BACKGROUND: &str = "CRA_CLIENT_WINDOW_BACKGROUND";
+const ENV_REACHABLE_STATUS_CODES: &str = "CRA_CLIENT_REACHABLE_STATUS_CODES";
+const ENV_SINGLE_INSTANCE: &str = "CRA_CLIENT_SINGLE_INSTANCE";
+const ENV_INACTIVITY_RESET_SECS: &str = "CRA_CLIENT_INACTIVITY_RESET_SECS";
+const ENV_REACHABILITY_POLL_SECS: &str = "CRA_CLIENT_REACHABILITY_POLL_SECS";
+const ENV_OFFLINE_TITLE_SUFFIX: &str = "CRA_CLIENT_OFFLINE_TITLE_SUFFIX";
+const ENV_STRICT_ALLOWED_HOSTS: &str = "CRA_CLIENT_STRICT_ALLOWED_HOSTS";
+const ENV_BLOCK_DATA_URLS: &str = "CRA_CLIENT_BLOCK_DATA_URLS";
+const ENV_DISABLE_GPU: &str = "CRA_CLIENT_DISABLE_GPU";
+const ENV_EXTRA_WEBVIEW_ARGS: &str = "CRA_CLIENT_EXTRA_WEBVIEW_ARGS";
+const ENV_WINDOW_ZOOM: &str = "CRA_CLIENT_WINDOW_ZOOM";
+const ENV_BOOTSTRAP_MAX_SECS: &str = "CRA_CLIENT_BOOTSTRAP_MAX_SECS";
+const ENV_ALLOW_HTTPS_DOWNGRADE: &str = "CRA_CLIENT_ALLOW_HTTPS_DOWNGRADE";
+const ENV_LOCALE: &str = "CRA_CLIENT_LOCALE";
+const ENV_REACHABILITY_METHOD: &str = "CRA_CLIENT_REACHABILITY_METHOD";
+const ENV_HEALTH_EXPECT_JSON_FIELD: &str = "CRA_CLIENT_HEALTH_EXPECT_JSON_FIELD";
+const ENV_STRICT_HTTPS: &str = "CRA_CLIENT_STRICT_HTTPS";
+const ENV_RETRY_ON_GATEWAY_ERRORS: &str = "CRA_CLIENT_RETRY_ON_GATEWAY_ERRORS";
+const ENV_RELEASE_LOCAL_HOSTS: &str = "CRA_CLIENT_RELEASE_LOCAL_HOSTS";
+const ENV_ALLOWED_HOSTS_URL: &str = "CRA_CLIENT_ALLOWED_HOSTS_URL";
+const DEFAULT_RELEASE_LOCAL_HOSTS: [&str; 4] = ["localhost", "127.0.0.1", "::1", "tauri.localhost"];
+const ALLOWED_HOST_PRECHECK_TIMEOUT: Duration = Duration::from_secs(3);
+const TRAY_SHOW: &str = "show";
+const TRAY_RELOAD: &str = "reload";
+const TRAY_QUIT: &str = "quit";
+const SYSTEM_PROXY_ENV_KEYS: [&str; 6] = [
+    "HTTPS_PROXY",
+    "https_proxy",
+    "HTTP_PROXY",
+    "http_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+];
+/// Keys `save_config` may write; everything else in client.env stays hand-edited only.
+const EDITABLE_CONFIG_KEYS: [&str; 12] = [
+    "APP_URL",
+    "ALLOWED_HOSTS",
+    "WINDOW_TITLE",
+    "WINDOW_WIDTH",
+    "WINDOW_HEIGHT",
+    "WINDOW_MIN_WIDTH",
+    "WINDOW_MIN_HEIGHT",
+    "WINDOW_MAX_WIDTH",
+    "WINDOW_MAX_HEIGHT",
+    "WINDOW_ZOOM",
+    "WINDOW_ICON_PATH",
+    "WINDOW_BACKGROUND",
+];
+/// Config files past either limit are skipped: a stray multi-gigabyte `client.env` must not
+/// exhaust memory at startup.
+const MAX_CONFIG_FILE_BYTES: u64 = 1024 * 1024;
+/// A health endpoint answers with a small status document; more than this is not one.
+const MAX_HEALTH_BODY_BYTES: u64 = 64 * 1024;
+const MAX_CONFIG_FILE_KEYS: usize = 1000;
+const CERT_PIN_MISMATCH: &str = "certificate does not match pinned SPKI fingerprint";
+const DISALLOWED_REDIRECT: &str = "redirect to a host outside ALLOWED_HOSTS";
+
+static LOG_MAX_BYTES: AtomicU64 = AtomicU64::new(DEFAULT_LOG_MAX_SIZE_MB * 1024 * 1024);
+static LOG_JSON: AtomicBool = AtomicBool::new(false);
+/// Serializes rotation and appends within this process; `startup.log.lock` covers other
+/// processes sharing the log folder. Also holds lines that could not be written yet.
+static LOG_WRITE_LOCK: Mutex<PendingLogLines> = Mutex::new(PendingLogLines {
+    lines: VecDeque::new(),
+    dropped: 0,
+});
+/// Most lines kept in memory while the log folder is unwritable; the oldest go first.
+const LOG_BUFFER_MAX_LINES: usize = 500;
+
+/// Log lines held back while `%APPDATA%` is unavailable, e.g. before a roaming profile is
+/// mounted. They are flushed, oldest first, by the next append that can write.
+struct PendingLogLines {
+    lines: VecDeque<String>,
+    dropped: usize,
+}
 
 const INIT_SCRIPT: &str = r#"
 (() => {
@@ -74,45 +234,997 @@ const INIT_SCRIPT: &str = r#"
         alert(`${info.title}\nVersion: ${info.version}\nTarget Host: ${info.app_host}`);
       });
     }
+
+    if (event.altKey && event.shiftKey && event.code === 'KeyC') {
+      void invoke('copy_diagnostics')
+        .then(() => alert('Diagnostics copied to clipboard.'))
+        .catch((error) => alert(`Could not copy diagnostics: ${error}`));
+    }
+
+    if (event.altKey && event.shiftKey && event.code === 'KeyL') {
+      void invoke('open_logs_folder').catch((error) => {
+        alert(`Could not open logs folder: ${error}`);
+      });
+    }
+
+    if (event.altKey && event.shiftKey && event.code === 'KeyD') {
+      void invoke('get_startup_diagnostics').then((entries) => {
+        alert(`Startup diagnostics\n\n${entries.join('\n')}`);
+      });
+    }
+
+    if (event.altKey && event.shiftKey && event.code === 'KeyI') {
+      void invoke('open_devtools').catch((error) => {
+        console.warn(`DevTools unavailable: ${error}`);
+      });
+    }
+
+    if (event.ctrlKey && !event.altKey && !event.shiftKey) {
+      const zoomCommand = {
+        Equal: 'zoom_in',
+        NumpadAdd: 'zoom_in',
+        Minus: 'zoom_out',
+        NumpadSubtract: 'zoom_out',
+        Digit0: 'reset_zoom',
+        Numpad0: 'reset_zoom',
+      }[event.code];
+      if (zoomCommand) {
+        event.preventDefault();
+        void invoke(zoomCommand).catch((error) => {
+          console.warn(`Zoom unavailable: ${error}`);
+        });
+      }
+    }
+
+    if (event.key === 'F5' || (event.ctrlKey && !event.altKey && event.code === 'KeyR')) {
+      event.preventDefault();
+      void invoke('reload_page').catch((error) => {
+        console.warn(`Reload blocked: ${error}`);
+      });
+    }
+
+    if (event.ctrlKey && event.shiftKey && !event.altKey && event.code === 'KeyP') {
+      event.preventDefault();
+      toggleHostSwitcher();
+    }
   });
+
+  // Ctrl+Shift+P palette over the ALLOWED_HOSTS entries; navigate_to_host does the checks.
+  let hostSwitcher = null;
+  const toggleHostSwitcher = () => {
+    if (window.top !== window) {
+      return;
+    }
+    if (hostSwitcher) {
+      hostSwitcher.remove();
+      hostSwitcher = null;
+      return;
+    }
+    const overlay = document.createElement('div');
+    overlay.style.cssText =
+      'position:fixed;inset:0;z-index:2147483647;display:flex;justify-content:center;' +
+      'align-items:flex-start;padding-top:12vh;background:rgba(9,15,23,0.55);' +
+      'font:14px "Segoe UI",Tahoma,sans-serif;';
+    const panel = document.createElement('div');
+    panel.style.cssText =
+      'width:min(420px,90vw);background:#08101b;color:#f7fbff;border-radius:10px;' +
+      'border:1px solid rgba(189,209,234,0.3);box-shadow:0 18px 36px rgba(4,10,18,0.5);';
+    const input = document.createElement('input');
+    input.placeholder = 'Switch host...';
+    input.style.cssText =
+      'box-sizing:border-box;width:100%;padding:10px 12px;border:0;outline:0;' +
+      'border-bottom:1px solid rgba(189,209,234,0.2);background:transparent;color:inherit;font:inherit;';
+    const list = document.createElement('div');
+    const message = document.createElement('div');
+    message.style.cssText = 'padding:8px 12px;color:#ffd6d6;';
+    panel.append(input, list, message);
+    overlay.append(panel);
+
+    let hosts = [];
+    let matches = [];
+    let selected = 0;
+    const close = () => {
+      overlay.remove();
+      hostSwitcher = null;
+    };
+    const render = () => {
+      const filter = input.value.trim().toLowerCase();
+      matches = hosts.filter((host) => host.includes(filter));
+      selected = Math.min(selected, Math.max(matches.length - 1, 0));
+      list.replaceChildren(
+        ...matches.map((host, index) => {
+          const row = document.createElement('div');
+          row.textContent = host;
+          row.style.cssText =
+            'padding:7px 12px;cursor:pointer;' +
+            (index === selected ? 'background:#2e76c0;' : '');
+          row.addEventListener('click', () => go(host));
+          return row;
+        }),
+      );
+    };
+    const go = (host) => {
+      message.textContent = `Checking ${host}...`;
+      void invoke('navigate_to_host', { host })
+        .then(close)
+        .catch((error) => {
+          message.textContent = error?.message ?? String(error);
+        });
+    };
+    overlay.addEventListener('click', (event) => {
+      if (event.target === overlay) {
+        close();
+      }
+    });
+    input.addEventListener('input', () => {
+      selected = 0;
+      render();
+    });
+    input.addEventListener('keydown', (event) => {
+      event.stopPropagation();
+      if (event.key === 'Escape') {
+        close();
+      } else if (event.key === 'ArrowDown' || event.key === 'ArrowUp') {
+        event.preventDefault();
+        const step = event.key === 'ArrowDown' ? 1 : -1;
+        selected = (selected + step + matches.length) % Math.max(matches.length, 1);
+        render();
+      } else if (event.key === 'Enter' && matches[selected]) {
+        go(matches[selected]);
+      }
+    });
+
+    hostSwitcher = overlay;
+    (document.body || document.documentElement).appendChild(overlay);
+    input.focus();
+    void invoke('list_allowed_hosts')
+      .then((entries) => {
+        hosts = entries;
+        render();
+      })
+      .catch((error) => {
+        message.textContent = `Hosts unavailable: ${error}`;
+      });
+  };
+
+  // Zoom is applied per document, so every page load restores the saved factor.
+  if (window.top === window) {
+    void invoke('get_zoom')
+      .then((factor) => {
+        const apply = () => {
+          if (document.documentElement) {
+            document.documentElement.style.zoom = String(factor);
+          }
+        };
+        if (document.documentElement) {
+          apply();
+        } else {
+          document.addEventListener('readystatechange', apply, { once: true });
+        }
+      })
+      .catch(() => {});
+  }
+
+  // Independent of KIOSK_MODE. Form fields stay selectable so typed text can be edited.
+  if (window.top === window) {
+    void invoke('get_input_restrictions')
+      .then((restrictions) => {
+        if (restrictions.disable_context_menu) {
+          window.addEventListener('contextmenu', (event) => event.preventDefault(), true);
+        }
+        if (!restrictions.disable_text_selection) {
+          return;
+        }
+        const style = document.createElement('style');
+        style.textContent =
+          'html, body { -webkit-user-select: none; user-select: none; }' +
+          ' input, textarea, select, [contenteditable]:not([contenteditable="false"]),' +
+          ' [contenteditable]:not([contenteditable="false"]) * { -webkit-user-select: text; user-select: text; }';
+        const attach = () => {
+          (document.head || document.documentElement).appendChild(style);
+        };
+        if (document.documentElement) {
+          attach();
+        } else {
+          document.addEventListener('readystatechange', attach, { once: true });
+        }
+      })
+      .catch(() => {});
+  }
+
+  // Every page load re-arms the timer, so the reset works however deep the app navigated.
+  if (window.top === window) {
+    void invoke('get_inactivity_reset_secs')
+      .then((seconds) => {
+        if (!seconds) {
+          return;
+        }
+        let timer;
+        const arm = () => {
+          clearTimeout(timer);
+          timer = setTimeout(() => {
+            void invoke('reset_to_home').catch((error) => {
+              console.warn(`Inactivity reset failed: ${error}`);
+            });
+          }, seconds * 1000);
+        };
+        for (const name of ['pointerdown', 'pointermove', 'keydown', 'wheel', 'touchstart']) {
+          window.addEventListener(name, arm, { capture: true, passive: true });
+        }
+        arm();
+      })
+      .catch(() => {});
+  }
+})();
+"#;
+
+const DEFAULT_PAGE_BACKGROUND: &str =
+    "radial-gradient(circle at top, #183049 0%, #0c1624 58%, #090f17 100%)";
+
+// Injected before any page script so the first paint already uses the configured color.
+const WINDOW_BACKGROUND_SCRIPT: &str = r#"
+(() => {
+  const apply = () => {
+    if (!document.documentElement) {
+      return false;
+    }
+    document.documentElement.style.backgroundColor = '{{background}}';
+    return true;
+  };
+  if (!apply()) {
+    document.addEventListener('readystatechange', apply, { once: true });
+  }
+})();
+"#;
+
+// Tauri 1 cannot intercept webview requests, so WEBVIEW_HEADERS are added to fetch and
+// XMLHttpRequest calls made by page scripts. Hosts are matched like `is_host_allowed`.
+const WEBVIEW_HEADERS_SCRIPT: &str = r#"
+(() => {
+  const headers = {{headers}};
+  const allowedHosts = {{allowed_hosts}};
+  const defaultPorts = { 'http:': '80', 'https:': '443' };
+
+  const isAllowed = (raw) => {
+    let url;
+    try {
+      url = new URL(String(raw), window.location.href);
+    } catch {
+      return false;
+    }
+    if (!(url.protocol in defaultPorts)) {
+      return false;
+    }
+    const host = url.hostname.toLowerCase().replace(/^\[(.*)\]$/, '$1');
+    const port = url.port || defaultPorts[url.protocol];
+    return allowedHosts.some((entry) => {
+      let pattern = entry;
+      let entryPort = null;
+      const bracketed = entry.match(/^\[(.*)\]:(\d+)$/);
+      if (bracketed) {
+        [, pattern, entryPort] = bracketed;
+      } else if (entry.split(':').length === 2) {
+        [pattern, entryPort] = entry.split(':');
+      }
+      if (entryPort !== null && entryPort !== port) {
+        return false;
+      }
+      if (pattern === '*') {
+        return true;
+      }
+      if (pattern.startsWith('.')) {
+        return pattern.length > 1 && host.length > pattern.length && host.endsWith(pattern);
+      }
+      return host === pattern;
+    });
+  };
+
+  const originalFetch = window.fetch;
+  window.fetch = (input, init) => {
+    const request = new Request(input, init);
+    if (isAllowed(request.url)) {
+      for (const [name, value] of headers) {
+        if (!request.headers.has(name)) {
+          request.headers.set(name, value);
+        }
+      }
+    }
+    return originalFetch.call(window, request);
+  };
+
+  // setRequestHeader appends to a header the page already set, so the names the page
+  // sets are tracked and skipped, as the fetch path does.
+  const originalOpen = XMLHttpRequest.prototype.open;
+  XMLHttpRequest.prototype.open = function (method, url, ...rest) {
+    this.__craWebviewHeaders = isAllowed(url);
+    this.__craPageHeaders = new Set();
+    return originalOpen.call(this, method, url, ...rest);
+  };
+  const originalSetRequestHeader = XMLHttpRequest.prototype.setRequestHeader;
+  XMLHttpRequest.prototype.setRequestHeader = function (name, value) {
+    this.__craPageHeaders?.add(String(name).toLowerCase());
+    return originalSetRequestHeader.call(this, name, value);
+  };
+  const originalSend = XMLHttpRequest.prototype.send;
+  XMLHttpRequest.prototype.send = function (body) {
+    if (this.__craWebviewHeaders) {
+      for (const [name, value] of headers) {
+        if (!this.__craPageHeaders?.has(name.toLowerCase())) {
+          originalSetRequestHeader.call(this, name, value);
+        }
+      }
+    }
+    return originalSend.call(this, body);
+  };
+})();
+"#;
+
+const KIOSK_SCRIPT: &str = r#"
+(() => {
+  document.addEventListener('contextmenu', (event) => event.preventDefault(), true);
+
+  window.addEventListener(
+    'keydown',
+    (event) => {
+      const devtools =
+        event.key === 'F12' ||
+        (event.ctrlKey && event.shiftKey && ['KeyI', 'KeyJ', 'KeyC'].includes(event.code));
+      if (devtools) {
+        event.preventDefault();
+        event.stopImmediatePropagation();
+        return;
+      }
+
+      if (event.ctrlKey && event.altKey && event.shiftKey && event.code === 'KeyQ') {
+        event.preventDefault();
+        const tauriObj = window.__TAURI__;
+        const invoke = tauriObj?.invoke ?? tauriObj?.core?.invoke;
+        void invoke?.('exit_kiosk');
+      }
+    },
+    true,
+  );
 })();
 "#;
 
+const SPLASH_HTML: &str = r#"<!doctype html>
+<html lang="en">
+  <head>
+    <meta charset="UTF-8" />
+    <title>{{title}}</title>
+    <style>
+      html, body {
+        margin: 0;
+        height: 100%;
+        font-family: "Segoe UI", Tahoma, sans-serif;
+        background: {{background}};
+        color: #f7fbff;
+        overflow: hidden;
+        user-select: none;
+      }
+      main {
+        height: 100%;
+        display: grid;
+        place-content: center;
+        justify-items: center;
+        gap: 14px;
+      }
+      .spinner {
+        width: 34px;
+        height: 34px;
+        border: 3px solid rgba(166, 197, 232, 0.35);
+        border-top-color: #f74d4d;
+        border-radius: 50%;
+        animation: spin 0.9s linear infinite;
+      }
+      h1 { margin: 0; font-size: 1.1rem; }
+      p { margin: 0; color: #a7b6c9; font-size: 0.86rem; }
+      @keyframes spin { to { transform: rotate(360deg); } }
+    </style>
+  </head>
+  <body>
+    <main>
+      <div class="spinner" aria-hidden="true"></div>
+      <h1>{{title}}</h1>
+      <p>Connecting to server...</p>
+    </main>
+  </body>
+</html>
+"#;
+
 #[derive(Clone, Debug)]
 struct RuntimeConfig {
+    profile: Option<String>,
+    locale: Locale,
     app_url: Url,
+    reachability_url: Url,
+    /// Every `APP_URL` entry in failover order; `app_url` is whichever one was selected.
+    app_targets: Vec<AppTarget>,
+    health_check_path: Option<String>,
+    server_info_path: String,
     allowed_hosts: HashSet<String>,
+    /// Central allowlist merged into `allowed_hosts` at load time.
+    allowed_hosts_url: Option<Url>,
+    /// Hosts rejected as `APP_URL` by the release localhost guard; `None` when it is off.
+    release_guard_hosts: Option<HashSet<String>>,
     window_title: String,
     window_width: f64,
     window_height: f64,
+    /// `WINDOW_WIDTH=80%` / `WINDOW_HEIGHT=80%`; `window_width` / `window_height` then hold
+    /// the defaults used when no monitor can be queried.
+    window_width_percent: Option<f64>,
+    window_height_percent: Option<f64>,
+    window_size_unit: WindowSizeUnit,
+    window_min_width: Option<f64>,
+    window_min_height: Option<f64>,
+    window_max_width: Option<f64>,
+    window_max_height: Option<f64>,
+    window_zoom: f64,
+    window_icon_path: Option<PathBuf>,
+    window_background: Option<String>,
     min_web_build_hash: Option<String>,
     enforce_web_build: bool,
+    reachability_timeout: Duration,
+    reachability_connect_timeout: Duration,
+    reachability_max_redirects: usize,
+    /// Probe with HEAD instead of GET; falls back to GET when the server rejects HEAD.
+    reachability_head: bool,
+    /// Re-request 502/503/504 answers within one check (rolling deploys).
+    retry_on_gateway_errors: bool,
+    /// `(field, expected)` that the JSON health response must contain to count as reachable.
+    health_expect_json_field: Option<(String, String)>,
+    retry_attempts: u32,
+    startup_grace: Duration,
+    inactivity_reset: Option<Duration>,
+    reachability_poll: Option<Duration>,
+    /// Appended to the window title while the watchdog sees the server down.
+    offline_title_suffix: String,
+    bootstrap_max: Duration,
+    log_max_bytes: u64,
+    pinned_cert_sha256: Vec<[u8; 32]>,
+    /// mTLS identity presented by reachability checks.
+    client_identity: Option<ClientIdentity>,
+    reachability_auth_header: Option<(HeaderName, HeaderValue)>,
+    /// Set when the header came from `APP_URL` userinfo: those credentials belong to the
+    /// primary origin and are not sent to failover targets.
+    reachability_auth_origin: Option<url::Origin>,
+    /// Sent by page fetch/XHR calls to allowed hosts, and by reachability requests.
+    webview_headers: Vec<(HeaderName, HeaderValue)>,
+    proxy_url: Option<Url>,
+    update_manifest_url: Option<Url>,
+    minimize_to_tray: bool,
+    kiosk_mode: bool,
+    disable_context_menu: bool,
+    disable_text_selection: bool,
+    single_instance: bool,
+    enable_devtools: bool,
+    precheck_allowed_hosts: bool,
+    strict_https: bool,
+    strict_allowed_hosts: bool,
+    /// Drop `data:` and `blob:` from the always-allowed navigation schemes.
+    block_data_urls: bool,
+    /// Turn off webview hardware acceleration; only read once, before the webview exists.
+    disable_gpu: bool,
+    /// `EXTRA_WEBVIEW_ARGS` flags, validated by `parse_extra_webview_args`.
+    extra_webview_args: Vec<String>,
+    allow_https_downgrade: bool,
+    audit_navigation: bool,
+    log_json: bool,
+    external_schemes: HashSet<String>,
+    reachable_status_codes: BTreeSet<u16>,
+    /// Where each resolved value came from (process env, client.env, or default).
+    value_sources: BTreeMap<&'static str, String>,
+}
+
+/// Language of user-facing strings. English is the fallback for any key a table lacks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Locale {
+    En,
+    Fr,
+}
+
+impl Locale {
+    /// Accepts a language tag such as `fr`, `fr-FR` or `fr_CA`; only the language is used.
+    fn parse(raw: &str) -> Option<Self> {
+        let language = raw
+            .trim()
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "en" => Some(Self::En),
+            "fr" => Some(Self::Fr),
+            _ => None,
+        }
+    }
+
+    fn code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::Fr => "fr",
+        }
+    }
+
+    fn strings(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::En => STRINGS_EN,
+            Self::Fr => STRINGS_FR,
+        }
+    }
+}
+
+const STRINGS_EN: &[(&str, &str)] = &[
+    (
+        "config.missing_required",
+        "Missing required setting: {key}. Set it via {env} or client.env.",
+    ),
+    (
+        "config.app_url_invalid",
+        "APP_URL must be a valid URL: {error}",
+    ),
+    ("config.app_url_scheme", "APP_URL must use HTTP or HTTPS."),
+    ("config.app_url_host", "APP_URL must include a host."),
+    (
+        "config.allowed_hosts_empty",
+        "ALLOWED_HOSTS must include at least one host.",
+    ),
+    (
+        "config.allowed_hosts_app_host",
+        "ALLOWED_HOSTS must include the APP_URL host and port.",
+    ),
+    (
+        "reach.unreachable",
+        "Could not reach server at {url}: {error}",
+    ),
+    (
+        "reach.refused",
+        "Nothing is listening on {host}:{port}. The server may be stopped or using another port.",
+    ),
+    (
+        "reach.reset",
+        "The connection to {host}:{port} was reset. A firewall or proxy may be dropping it.",
+    ),
+    (
+        "reach.dns",
+        "Host {host} not found. Check the address and the network's DNS settings.",
+    ),
+    ("reach.timeout", "{host}:{port} did not respond in time."),
+    (
+        "reach.status",
+        "Server responded with status {status} when requesting {url}",
+    ),
+    (
+        "reach.maintenance",
+        "{host} is down for scheduled maintenance.",
+    ),
+    (
+        "reach.not_html",
+        "{url} answered with {content_type}, not a web page. APP_URL may point at an API instead of the web app.",
+    ),
+    (
+        "reach.health_not_json",
+        "Health response from {url} is not JSON: {error}",
+    ),
+    (
+        "reach.health_mismatch",
+        "Health check at {url} expected {field}={expected}, got {actual}.",
+    ),
+    (
+        "reach.pin_mismatch",
+        "TLS certificate of {host} does not match any PINNED_CERT_SHA256 fingerprint.",
+    ),
+    (
+        "reach.disallowed_redirect",
+        "Server redirected to disallowed host {host} (from {url}). Add it to ALLOWED_HOSTS if it is expected.",
+    ),
+    ("offline.heading", "Server unreachable"),
+    ("offline.could_not_connect", "Could not connect to {host}."),
+    ("offline.no_response", "The server did not respond."),
+    ("offline.retry", "Retry"),
+    ("offline.retrying", "Retrying connection..."),
+    (
+        "offline.retry_progress",
+        "Retry {attempt} of {total} failed, next attempt in {seconds}s.",
+    ),
+];
+
+const STRINGS_FR: &[(&str, &str)] = &[
+    (
+        "config.missing_required",
+        "Paramètre obligatoire manquant : {key}. Définissez-le via {env} ou client.env.",
+    ),
+    (
+        "config.app_url_invalid",
+        "APP_URL doit être une URL valide : {error}",
+    ),
+    (
+        "config.app_url_scheme",
+        "APP_URL doit utiliser HTTP ou HTTPS.",
+    ),
+    ("config.app_url_host", "APP_URL doit inclure un hôte."),
+    (
+        "config.allowed_hosts_empty",
+        "ALLOWED_HOSTS doit contenir au moins un hôte.",
+    ),
+    (
+        "config.allowed_hosts_app_host",
+        "ALLOWED_HOSTS doit inclure l'hôte et le port de APP_URL.",
+    ),
+    (
+        "reach.unreachable",
+        "Impossible de joindre le serveur {url} : {error}",
+    ),
+    (
+        "reach.refused",
+        "Aucun service n'écoute sur {host}:{port}. Le serveur est peut-être arrêté ou utilise un autre port.",
+    ),
+    (
+        "reach.reset",
+        "La connexion à {host}:{port} a été réinitialisée. Un pare-feu ou un proxy la coupe peut-être.",
+    ),
+    (
+        "reach.dns",
+        "Hôte {host} introuvable. Vérifiez l'adresse et les paramètres DNS du réseau.",
+    ),
+    ("reach.timeout", "{host}:{port} n'a pas répondu à temps."),
+    (
+        "reach.status",
+        "Le serveur a répondu avec le statut {status} pour {url}",
+    ),
+    (
+        "reach.maintenance",
+        "{host} est en maintenance programmée.",
+    ),
+    (
+        "reach.not_html",
+        "{url} a répondu avec {content_type} et non une page web. APP_URL pointe peut-être vers une API au lieu de l'application web.",
+    ),
+    (
+        "reach.health_not_json",
+        "La réponse de santé de {url} n'est pas du JSON : {error}",
+    ),
+    (
+        "reach.health_mismatch",
+        "Le contrôle de santé de {url} attendait {field}={expected}, reçu {actual}.",
+    ),
+    (
+        "reach.pin_mismatch",
+        "Le certificat TLS de {host} ne correspond à aucune empreinte de PINNED_CERT_SHA256.",
+    ),
+    (
+        "reach.disallowed_redirect",
+        "Le serveur a redirigé vers l'hôte non autorisé {host} (depuis {url}). Ajoutez-le à ALLOWED_HOSTS s'il est attendu.",
+    ),
+    ("offline.heading", "Serveur injoignable"),
+    (
+        "offline.could_not_connect",
+        "Connexion à {host} impossible.",
+    ),
+    ("offline.no_response", "Le serveur n'a pas répondu."),
+    ("offline.retry", "Réessayer"),
+    ("offline.retrying", "Nouvelle tentative de connexion..."),
+    (
+        "offline.retry_progress",
+        "Tentative {attempt} sur {total} échouée, prochaine tentative dans {seconds} s.",
+    ),
+];
+
+/// Looks `key` up for `locale`, falling back to English and then to the key itself.
+fn tr(locale: Locale, key: &'static str) -> &'static str {
+    let lookup = |strings: &'static [(&'static str, &'static str)]| {
+        strings
+            .iter()
+            .find(|(candidate, _)| *candidate == key)
+            .map(|(_, text)| *text)
+    };
+    lookup(locale.strings())
+        .or_else(|| lookup(STRINGS_EN))
+        .unwrap_or(key)
 }
 
 #[derive(Clone, Debug)]
-struct AppState {
+struct AppTarget {
+    app_url: Url,
+    reachability_url: Url,
+}
+
+#[derive(Clone, Debug)]
+struct RuntimeSnapshot {
     config: Option<RuntimeConfig>,
-    config_error: Option<String>,
+    config_error: Option<ConfigError>,
+    diagnostics: Vec<String>,
+    /// Built once per config so reachability checks reuse pooled connections and TLS
+    /// sessions. Always `Some` when `config` is.
+    reachability_client: Option<ReachabilityClient>,
 }
 
-#[derive(Serialize)]
+type ReachabilityOutcome = (Instant, Result<ReachabilityProbe, String>);
+
+/// Reachability HTTP client plus per-URL coalescing: concurrent checks of the same URL share
+/// one request, and a success is reused for `REACHABILITY_RESULT_TTL`. Failures are not
+/// cached, so Retry's backoff always probes again.
+#[derive(Clone, Debug)]
+struct ReachabilityClient {
+    http: reqwest::Client,
+    checks: Arc<Mutex<HashMap<String, Arc<tokio::sync::OnceCell<ReachabilityOutcome>>>>>,
+    /// URLs whose last probe got 503 with `Retry-After`; any other outcome clears the entry.
+    maintenance: Arc<Mutex<HashMap<String, MaintenanceWindow>>>,
+}
+
+/// A 503 + `Retry-After` answer. Only the delta-seconds form yields `retry_after_secs`;
+/// an HTTP-date still marks maintenance but without a countdown.
+#[derive(Clone, Copy, Debug)]
+struct MaintenanceWindow {
+    retry_after_secs: Option<u64>,
+}
+
+impl ReachabilityClient {
+    fn new(http: reqwest::Client) -> Self {
+        Self {
+            http,
+            checks: Arc::new(Mutex::new(HashMap::new())),
+            maintenance: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn record_maintenance(&self, url: &Url, window: Option<MaintenanceWindow>) {
+        let mut guard = match self.maintenance.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        match window {
+            Some(window) => guard.insert(url.to_string(), window),
+            None => guard.remove(url.as_str()),
+        };
+    }
+
+    fn maintenance(&self, url: &Url) -> Option<MaintenanceWindow> {
+        match self.maintenance.lock() {
+            Ok(guard) => guard.get(url.as_str()).copied(),
+            Err(poisoned) => poisoned.into_inner().get(url.as_str()).copied(),
+        }
+    }
+
+    async fn coalesce<F>(&self, url: &Url, probe: F) -> Result<ReachabilityProbe, String>
+    where
+        F: std::future::Future<Output = Result<ReachabilityProbe, String>>,
+    {
+        let check = {
+            let mut checks = match self.checks.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            let reusable = checks.get(url.as_str()).filter(|check| match check.get() {
+                None => true,
+                Some((completed, Ok(_))) => completed.elapsed() < REACHABILITY_RESULT_TTL,
+                Some((_, Err(_))) => false,
+            });
+            match reusable {
+                Some(check) => check.clone(),
+                None => {
+                    let check = Arc::new(tokio::sync::OnceCell::new());
+                    checks.insert(url.to_string(), check.clone());
+                    check
+                }
+            }
+        };
+
+        check
+            .get_or_init(|| async move {
+                let result = probe.await;
+                (Instant::now(), result)
+            })
+            .await
+            .1
+            .clone()
+    }
+}
+
+/// Shared state managed by Tauri.
+///
+/// Locking discipline: the lock is only held long enough to clone or swap the snapshot.
+/// Commands take a `snapshot()` up front and work on the copy, so no guard is ever held
+/// across an `.await` or while calling another command, and `replace()` never blocks on
+/// an in-flight reachability check.
+#[derive(Debug)]
+struct AppState {
+    runtime: RwLock<RuntimeSnapshot>,
+    bootstrap_result: RwLock<Option<BootstrapState>>,
+    startup_grace_used: AtomicBool,
+    /// Bumped whenever the config changes; a watchdog task exits once its value is stale.
+    watchdog_generation: AtomicU64,
+    update_info: RwLock<UpdateInfo>,
+    /// Last URL `on_navigation` allowed, logged as the referrer of blocked navigations.
+    last_allowed_url: Mutex<Option<Url>>,
+    /// Zoom chosen with the zoom commands; `None` until the user changes it this session.
+    zoom_factor: Mutex<Option<f64>>,
+    /// Hosts ever loaded over https, persisted so they are never downgraded to http.
+    https_seen_hosts: Mutex<HashSet<String>>,
+}
+
+impl AppState {
+    fn new(snapshot: RuntimeSnapshot) -> Self {
+        Self {
+            runtime: RwLock::new(snapshot),
+            bootstrap_result: RwLock::new(None),
+            startup_grace_used: AtomicBool::new(false),
+            watchdog_generation: AtomicU64::new(0),
+            update_info: RwLock::new(UpdateInfo::default()),
+            last_allowed_url: Mutex::new(None),
+            zoom_factor: Mutex::new(None),
+            https_seen_hosts: Mutex::new(load_https_seen_hosts()),
+        }
+    }
+
+    /// Remembers `url`'s host when it was loaded over https, saving the set when it grows.
+    fn record_https_host(&self, url: &Url) {
+        let Some(host) = url
+            .host_str()
+            .map(normalize_host)
+            .filter(|host| url.scheme() == "https" && !is_internal_navigation_host(host))
+        else {
+            return;
+        };
+        let mut guard = match self.https_seen_hosts.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if guard.insert(host.clone()) {
+            append_startup_log_entry(&format!("https_seen_host_added host={host}"));
+            save_https_seen_hosts(&guard);
+        }
+    }
+
+    fn is_https_seen_host(&self, host: &str) -> bool {
+        match self.https_seen_hosts.lock() {
+            Ok(guard) => guard.contains(host),
+            Err(poisoned) => poisoned.into_inner().contains(host),
+        }
+    }
+
+    /// The zoom set this session, else the one saved by a previous run, else `WINDOW_ZOOM`.
+    fn zoom_factor(&self) -> f64 {
+        let current = match self.zoom_factor.lock() {
+            Ok(guard) => *guard,
+            Err(poisoned) => *poisoned.into_inner(),
+        };
+        current.or_else(load_zoom_factor).unwrap_or_else(|| {
+            self.snapshot()
+                .config
+                .map_or(DEFAULT_WINDOW_ZOOM, |config| config.window_zoom)
+        })
+    }
+
+    fn set_zoom_factor(&self, factor: f64) {
+        match self.zoom_factor.lock() {
+            Ok(mut guard) => *guard = Some(factor),
+            Err(poisoned) => *poisoned.into_inner() = Some(factor),
+        }
+        save_zoom_factor(factor);
+    }
+
+    /// Points the live config at a failover target picked by `check_app_targets`, unless
+    /// the config was reloaded in the meantime and no longer lists it.
+    fn select_app_target(&self, selected: &RuntimeConfig) {
+        let mut guard = match self.runtime.write() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Some(config) = guard.config.as_mut().filter(|config| {
+            config.app_url != selected.app_url
+                && config
+                    .app_targets
+                    .iter()
+                    .any(|target| target.app_url == selected.app_url)
+        }) {
+            config.app_url = selected.app_url.clone();
+            config.reachability_url = selected.reachability_url.clone();
+        }
+    }
+
+    fn update_info(&self) -> UpdateInfo {
+        match self.update_info.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    fn set_update_info(&self, info: UpdateInfo) {
+        match self.update_info.write() {
+            Ok(mut guard) => *guard = info,
+            Err(poisoned) => *poisoned.into_inner() = info,
+        }
+    }
+
+    fn record_allowed_navigation(&self, url: &Url) {
+        match self.last_allowed_url.lock() {
+            Ok(mut guard) => *guard = Some(url.clone()),
+            Err(poisoned) => *poisoned.into_inner() = Some(url.clone()),
+        }
+    }
+
+    fn navigation_referrer(&self) -> String {
+        let last = match self.last_allowed_url.lock() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        last.map_or_else(|| "-".to_string(), |url| redacted_url(&url))
+    }
+
+    fn last_bootstrap_result(&self) -> Option<BootstrapState> {
+        match self.bootstrap_result.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Stores the latest bootstrap outcome and rewrites `bootstrap-result.json` when the
+    /// reachability outcome differs from the previous one (or nothing was written yet).
+    fn record_bootstrap_result(&self, result: &BootstrapState) {
+        let changed = match self.last_bootstrap_result() {
+            Some(previous) => {
+                previous.reachable != result.reachable
+                    || previous.config_error != result.config_error
+                    || previous.app_url != result.app_url
+                    || previous.reachability_error != result.reachability_error
+            }
+            None => true,
+        };
+        match self.bootstrap_result.write() {
+            Ok(mut guard) => *guard = Some(result.clone()),
+            Err(poisoned) => *poisoned.into_inner() = Some(result.clone()),
+        }
+        if changed {
+            write_bootstrap_result_file(result);
+        }
+    }
+
+    fn snapshot(&self) -> RuntimeSnapshot {
+        match self.runtime.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    fn replace(&self, snapshot: RuntimeSnapshot) {
+        match self.runtime.write() {
+            Ok(mut guard) => *guard = snapshot,
+            Err(poisoned) => *poisoned.into_inner() = snapshot,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
 struct BootstrapState {
     ready: bool,
     config_error: Option<String>,
     app_url: Option<String>,
     app_host: Option<String>,
+    allowed_hosts: Vec<String>,
     window_title: String,
     window_width: f64,
     window_height: f64,
     version: String,
     reachable: bool,
     reachability_error: Option<String>,
+    reachability_latency_ms: Option<u64>,
+    reachability_status: Option<u16>,
     web_build_hash: Option<String>,
     web_build_time: Option<String>,
     required_web_build_hash: Option<String>,
     build_parity_ok: bool,
     build_parity_error: Option<String>,
     enforce_web_build: bool,
+    /// A saved last-session URL exists and its host is still allowed.
+    resumable: bool,
+    /// `CommandError` code for whatever stops the launch, `None` when it can proceed.
+    error_code: Option<&'static str>,
+    /// The server reported scheduled maintenance (503 + `Retry-After`).
+    maintenance: bool,
+    retry_after_secs: Option<u64>,
+    /// `APP_URL` answered with a non-HTML `Content-Type`; the launch still proceeds.
+    content_type_warning: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -121,6 +1233,8 @@ struct AboutInfo {
     version: String,
     app_host: String,
     app_url: String,
+    profile: Option<String>,
+    locale: &'static str,
     required_web_build_hash: Option<String>,
     enforce_web_build: bool,
     web_build_hash: Option<String>,
@@ -128,6 +1242,23 @@ struct AboutInfo {
     web_build_error: Option<String>,
 }
 
+#[derive(Deserialize, Debug)]
+struct UpdateManifest {
+    version: String,
+    #[serde(default)]
+    download_url: Option<String>,
+    #[serde(default)]
+    notes: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+struct UpdateInfo {
+    available: bool,
+    latest_version: Option<String>,
+    download_url: Option<String>,
+    notes: Option<String>,
+}
+
 #[derive(Deserialize, Debug)]
 struct DeployInfoResponse {
     #[serde(default)]
@@ -150,61 +1281,440 @@ struct DeployInfoGit {
     hash: Option<String>,
 }
 
-#[derive(Debug, Clone)]
-struct BuildParityResult {
-    web_build_hash: Option<String>,
+/// Error from the launch commands, serialized as `{code, message}` so the UI can pick a
+/// recovery action from `code` instead of matching message text.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "code", content = "message", rename_all = "snake_case")]
+enum CommandError {
+    /// `APP_URL` or `ALLOWED_HOSTS` is not set anywhere.
+    ConfigMissing(String),
+    ConfigInvalid(String),
+    Unreachable(String),
+    /// The server answered 503 with `Retry-After`: scheduled downtime, not an outage.
+    Maintenance(String),
+    /// The server build fails `MIN_WEB_BUILD_HASH` while `ENFORCE_WEB_BUILD` is on.
+    BuildParity(String),
+    /// The target would be rejected by the navigation allowlist.
+    NavigationBlocked(String),
+    /// The window could not be navigated or shown.
+    Window(String),
+}
+
+impl CommandError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::ConfigMissing(_) => "config_missing",
+            Self::ConfigInvalid(_) => "config_invalid",
+            Self::Unreachable(_) => "unreachable",
+            Self::Maintenance(_) => "maintenance",
+            Self::BuildParity(_) => "build_parity",
+            Self::NavigationBlocked(_) => "navigation_blocked",
+            Self::Window(_) => "window",
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            Self::ConfigMissing(message)
+            | Self::ConfigInvalid(message)
+            | Self::Unreachable(message)
+            | Self::Maintenance(message)
+            | Self::BuildParity(message)
+            | Self::NavigationBlocked(message)
+            | Self::Window(message) => message,
+        }
+    }
+
+    fn from_snapshot(snapshot: &RuntimeSnapshot) -> Self {
+        match snapshot.config_error.clone() {
+            Some(ConfigError::Missing(message)) => Self::ConfigMissing(message),
+            Some(ConfigError::Invalid(message)) => Self::ConfigInvalid(message),
+            None => Self::ConfigMissing("Runtime configuration missing.".to_string()),
+        }
+    }
+}
+
+/// Why `load_runtime_config` failed. Plain `String` errors from the parsers are `Invalid`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ConfigError {
+    /// `APP_URL` or `ALLOWED_HOSTS` is not set anywhere.
+    Missing(String),
+    Invalid(String),
+}
+
+impl ConfigError {
+    fn message(&self) -> &str {
+        match self {
+            Self::Missing(message) | Self::Invalid(message) => message,
+        }
+    }
+}
+
+impl From<String> for ConfigError {
+    fn from(message: String) -> Self {
+        Self::Invalid(message)
+    }
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str(self.message())
+    }
+}
+
+// Commands that still return plain strings keep working with `?`.
+impl From<CommandError> for String {
+    fn from(error: CommandError) -> Self {
+        error.message().to_string()
+    }
+}
+
+#[derive(Serialize)]
+struct ConnectionTestResult {
+    reachable: bool,
+    latency_ms: Option<u64>,
+    status: Option<u16>,
+    error: Option<String>,
+}
+
+/// Emitted after each failed `retry_connect` attempt; `next_delay_ms` is `None` once the
+/// retry budget is spent.
+#[derive(Clone, Serialize)]
+struct RetryProgress {
+    attempt: u32,
+    total: u32,
+    next_delay_ms: Option<u64>,
+    error: String,
+}
+
+#[derive(Serialize)]
+struct AppUrlCheck {
+    name: &'static str,
+    passed: bool,
+    message: Option<String>,
+}
+
+/// Checks run in order and stop at the first failure, so `checks` may be shorter than
+/// the full list.
+#[derive(Serialize)]
+struct AppUrlValidation {
+    valid: bool,
+    checks: Vec<AppUrlCheck>,
+}
+
+#[derive(Clone, Serialize)]
+struct ReachabilityChanged {
+    reachable: bool,
+    status: Option<u16>,
+    latency_ms: Option<u64>,
+    error: Option<String>,
+}
+
+/// `json` is set when the body parses as JSON, otherwise `text` holds the (truncated) body.
+#[derive(Serialize)]
+struct ServerInfo {
+    url: String,
+    status: u16,
+    json: Option<serde_json::Value>,
+    text: Option<String>,
+}
+
+#[derive(Default, Serialize)]
+struct ClearLocalStateResult {
+    removed: Vec<String>,
+    /// `<path>: <error>` for each item that exists but could not be removed.
+    failed: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct DnsInfo {
+    host: String,
+    addresses: Vec<String>,
+    error: Option<String>,
+}
+
+/// Outcome of a successful reachability request.
+#[derive(Clone, Debug)]
+struct ReachabilityProbe {
+    latency_ms: u64,
+    status: u16,
+    content_type_warning: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+struct WindowGeometry {
+    x: i32,
+    y: i32,
+    width: f64,
+    height: f64,
+}
+
+#[derive(Debug, Clone)]
+struct BuildParityResult {
+    web_build_hash: Option<String>,
     web_build_time: Option<String>,
     parity_ok: bool,
     parity_error: Option<String>,
 }
 
 #[tauri::command]
-async fn bootstrap_state(state: State<'_, AppState>) -> Result<BootstrapState, String> {
+async fn bootstrap_state(state: State<'_, AppState>) -> Result<BootstrapState, CommandError> {
+    // STARTUP_GRACE_SECS only covers the very first bootstrap of the process.
+    let apply_grace = !state.startup_grace_used.swap(true, Ordering::SeqCst);
+    let result = resolve_bootstrap_state(&state, apply_grace).await;
+    state.record_bootstrap_result(&result);
+    Ok(result)
+}
+
+#[tauri::command]
+async fn reload_config(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<BootstrapState, String> {
+    // Reload the running profile, so one picked with switch_profile is not dropped.
+    let (config_result, diagnostics) = load_runtime_config_blocking(active_profile(&state)).await;
+    append_startup_log_entry("----- CRA Client reload_config -----");
+    for entry in &diagnostics {
+        append_startup_log_entry(entry);
+    }
+
+    let snapshot = runtime_snapshot_from(config_result, diagnostics, "reload_result");
+    state.replace(snapshot);
+    restart_reachability_watchdog(&app);
+    let result = resolve_bootstrap_state(&state, false).await;
+    state.record_bootstrap_result(&result);
+    Ok(result)
+}
+
+fn active_profile(state: &AppState) -> Option<String> {
+    state.snapshot().config.and_then(|config| config.profile)
+}
+
+/// Runs load_runtime_config on the blocking pool, since an ALLOWED_HOSTS_URL download can
+/// take up to the reachability timeout and must not stall an async worker.
+async fn load_runtime_config_blocking(
+    profile: Option<String>,
+) -> (Result<RuntimeConfig, ConfigError>, Vec<String>) {
+    tauri::async_runtime::spawn_blocking(move || load_runtime_config(profile.as_deref()))
+        .await
+        .unwrap_or_else(|error| {
+            (
+                Err(format!("Config load failed: {error}").into()),
+                Vec::new(),
+            )
+        })
+}
+
+/// Writes `values` into `%APPDATA%\CRA Client\client.env` and reloads. Existing lines are
+/// updated in place so comments and ordering survive; new keys are appended and an empty
+/// value removes the key. The previous file is kept as `client.env.bak` and restored when
+/// the new config fails to load.
+#[tauri::command]
+async fn save_config(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    values: HashMap<String, String>,
+) -> Result<BootstrapState, String> {
+    let mut updates = BTreeMap::new();
+    for (key, value) in values {
+        let key = key.trim().to_ascii_uppercase();
+        if !EDITABLE_CONFIG_KEYS.contains(&key.as_str()) {
+            return Err(format!(
+                "{key} cannot be changed from the client. Editable keys: {}.",
+                EDITABLE_CONFIG_KEYS.join(", ")
+            ));
+        }
+        if value.contains(['\n', '\r']) {
+            return Err(format!("{key} must be a single line."));
+        }
+        // client.env has no quote escape, so such a value could not be read back as saved.
+        if value.contains('\'') && value.contains('"') {
+            return Err(format!(
+                "{key} cannot contain both single and double quotes."
+            ));
+        }
+        updates.insert(key, value.trim().to_string());
+    }
+
+    let path = appdata_client_env_path()
+        .ok_or_else(|| "Could not resolve client.env: APPDATA is not set.".to_string())?;
+    let previous = match fs::read_to_string(&path) {
+        Ok(content) => Some(content),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+        Err(error) => return Err(format!("Could not read '{}': {error}", path.display())),
+    };
+    let backup_path = path.with_file_name("client.env.bak");
+    if let Some(content) = &previous {
+        fs::write(&backup_path, content).map_err(|error| {
+            format!(
+                "Could not write backup '{}': {error}",
+                backup_path.display()
+            )
+        })?;
+    } else if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Could not create '{}': {error}", parent.display()))?;
+    }
+
+    let content = update_client_env_content(previous.as_deref().unwrap_or_default(), &updates);
+    fs::write(&path, content)
+        .map_err(|error| format!("Could not write '{}': {error}", path.display()))?;
+
+    // Validate the profile that is running; reload_config below keeps it too.
+    let (config_result, _) = load_runtime_config_blocking(active_profile(&state)).await;
+    if let Err(error) = config_result {
+        let restored = match &previous {
+            Some(content) => fs::write(&path, content),
+            None => fs::remove_file(&path),
+        };
+        append_startup_log_entry(&format!(
+            "save_config=rolled_back keys={} error={error}",
+            updates.keys().cloned().collect::<Vec<_>>().join(",")
+        ));
+        return Err(match restored {
+            Ok(()) => format!("The new configuration is invalid and was not saved: {error}"),
+            Err(restore_error) => format!(
+                "The new configuration is invalid: {error}. Restoring client.env failed ({restore_error}); the previous file is at '{}'.",
+                backup_path.display()
+            ),
+        });
+    }
+
+    append_startup_log_entry(&format!(
+        "save_config=saved keys={}",
+        updates.keys().cloned().collect::<Vec<_>>().join(",")
+    ));
+    reload_config(app, state).await
+}
+
+fn update_client_env_content(content: &str, updates: &BTreeMap<String, String>) -> String {
+    let mut written = HashSet::new();
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let key = trimmed
+            .split_once('=')
+            .filter(|_| !trimmed.starts_with('#'))
+            .map(|(key, _)| {
+                let key = key.trim();
+                key.strip_prefix("export ").map(str::trim).unwrap_or(key)
+            });
+        match key.and_then(|key| updates.get_key_value(key)) {
+            Some((key, value)) => {
+                // Only the first occurrence is kept; later duplicates would shadow the edit.
+                if !value.is_empty() && written.insert(key.clone()) {
+                    let prefix = if trimmed.starts_with("export ") {
+                        "export "
+                    } else {
+                        ""
+                    };
+                    lines.push(format!("{prefix}{key}={}", quote_env_value(value)));
+                }
+            }
+            None => lines.push(line.to_string()),
+        }
+    }
+    for (key, value) in updates {
+        if !value.is_empty() && !written.contains(key) {
+            lines.push(format!("{key}={}", quote_env_value(value)));
+        }
+    }
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    updated
+}
+
+/// Quotes values that `parse_client_env_file` would otherwise trim, cut at a comment or
+/// expand. Single quotes keep the value literal; inside double quotes, used when the value
+/// has a `'`, every `${` is written as `$${` so it is not expanded on load. save_config
+/// rejects values holding both quote characters, which no quoting can round-trip.
+fn quote_env_value(value: &str) -> String {
+    if !value.contains([' ', '\t', '#', '$', '"', '\'']) {
+        return value.to_string();
+    }
+    if value.contains('\'') {
+        format!("\"{}\"", value.replace("${", "$${"))
+    } else {
+        format!("'{value}'")
+    }
+}
+
+async fn resolve_bootstrap_state(state: &AppState, apply_grace: bool) -> BootstrapState {
     let version = env!("CARGO_PKG_VERSION").to_string();
+    let snapshot = state.snapshot();
 
-    if let Some(config_error) = &state.config_error {
-        return Ok(BootstrapState {
+    if let Some(config_error) = &snapshot.config_error {
+        let error_code = CommandError::from_snapshot(&snapshot).code();
+        return BootstrapState {
             ready: false,
-            config_error: Some(config_error.clone()),
+            config_error: Some(config_error.to_string()),
             app_url: None,
             app_host: None,
+            allowed_hosts: Vec::new(),
             window_title: DEFAULT_TITLE.to_string(),
             window_width: DEFAULT_WIDTH,
             window_height: DEFAULT_HEIGHT,
             version,
             reachable: false,
             reachability_error: None,
+            reachability_latency_ms: None,
+            reachability_status: None,
             web_build_hash: None,
             web_build_time: None,
             required_web_build_hash: None,
             build_parity_ok: true,
             build_parity_error: None,
             enforce_web_build: false,
-        });
+            resumable: false,
+            error_code: Some(error_code),
+            maintenance: false,
+            retry_after_secs: None,
+            content_type_warning: None,
+        };
     }
 
-    let Some(config) = &state.config else {
-        return Ok(BootstrapState {
+    let (Some(config), Some(client)) = (&snapshot.config, &snapshot.reachability_client) else {
+        return BootstrapState {
             ready: false,
             config_error: Some("Runtime configuration is missing.".to_string()),
             app_url: None,
             app_host: None,
+            allowed_hosts: Vec::new(),
             window_title: DEFAULT_TITLE.to_string(),
             window_width: DEFAULT_WIDTH,
             window_height: DEFAULT_HEIGHT,
             version,
             reachable: false,
             reachability_error: None,
+            reachability_latency_ms: None,
+            reachability_status: None,
             web_build_hash: None,
             web_build_time: None,
             required_web_build_hash: None,
             build_parity_ok: true,
             build_parity_error: None,
             enforce_web_build: false,
-        });
+            resumable: false,
+            error_code: Some("config_missing"),
+            maintenance: false,
+            retry_after_secs: None,
+            content_type_warning: None,
+        };
     };
 
-    let reachability = check_server_reachable(&config.app_url).await;
+    let reachability = if apply_grace && !config.startup_grace.is_zero() {
+        check_server_reachable_with_grace(config, client).await
+    } else {
+        check_app_targets(config, client).await
+    };
+    let (config, reachability) = match reachability {
+        Ok((selected, probe)) => {
+            state.select_app_target(&selected);
+            (selected, Ok(probe))
+        }
+        Err(error) => (config.clone(), Err(error)),
+    };
+    let config = &config;
     let build_parity = check_web_build_parity(config).await;
     let parity_ok = build_parity.parity_ok;
     let parity_error = build_parity.parity_error.clone();
@@ -222,16 +1732,37 @@ async fn bootstrap_state(state: State<'_, AppState>) -> Result<BootstrapState, S
         parity_error.clone().unwrap_or_else(|| "-".to_string())
     ));
 
-    Ok(BootstrapState {
+    let maintenance = reachability
+        .is_err()
+        .then(|| client.maintenance(&config.reachability_url))
+        .flatten();
+    let error_code = if maintenance.is_some() {
+        Some("maintenance")
+    } else if reachability.is_err() {
+        Some("unreachable")
+    } else if !parity_ok && config.enforce_web_build {
+        Some("build_parity")
+    } else {
+        None
+    };
+
+    BootstrapState {
         ready: true,
         config_error: None,
         app_url: Some(config.app_url.to_string()),
         app_host: config.app_url.host_str().map(ToString::to_string),
-        window_title: config.window_title.clone(),
+        allowed_hosts: sorted_entries(&config.allowed_hosts),
+        window_title: render_window_title(config),
         window_width: config.window_width,
         window_height: config.window_height,
         version,
         reachable: reachability.is_ok(),
+        reachability_latency_ms: reachability.as_ref().ok().map(|probe| probe.latency_ms),
+        reachability_status: reachability.as_ref().ok().map(|probe| probe.status),
+        content_type_warning: reachability
+            .as_ref()
+            .ok()
+            .and_then(|probe| probe.content_type_warning.clone()),
         reachability_error: reachability.err(),
         web_build_hash: build_parity.web_build_hash,
         web_build_time: build_parity.web_build_time,
@@ -239,18 +1770,411 @@ async fn bootstrap_state(state: State<'_, AppState>) -> Result<BootstrapState, S
         build_parity_ok: parity_ok,
         build_parity_error: parity_error,
         enforce_web_build: config.enforce_web_build,
+        resumable: resumable_session_url(config).is_some(),
+        error_code,
+        maintenance: maintenance.is_some(),
+        retry_after_secs: maintenance.and_then(|window| window.retry_after_secs),
+    }
+}
+
+#[tauri::command]
+async fn launch_app(window: Window, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let (config, client) = get_config_with_client(&state)?;
+    let (config, _) = check_app_targets(&config, &client)
+        .await
+        .map_err(|error| reachability_command_error(&client, &config, error))?;
+    state.select_app_target(&config);
+    navigate_to_app(&window, &config).await
+}
+
+/// Reopens the last fully-loaded page when its host is still allowed and answers the
+/// reachability check, otherwise behaves like `launch_app`.
+#[tauri::command]
+async fn resume_last_session(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let (config, client) = get_config_with_client(&state)?;
+    if let Some(url) = resumable_session_url(&config) {
+        // Probe through the matching APP_URL target so HEALTH_CHECK_PATH still applies.
+        let mut candidate = config.clone();
+        candidate.reachability_url = config
+            .app_targets
+            .iter()
+            .find(|target| target.app_url.origin() == url.origin())
+            .map_or_else(|| url.clone(), |target| target.reachability_url.clone());
+        match check_server_reachable(&candidate, &client).await {
+            Ok(_) => {
+                append_startup_log_entry(&format!(
+                    "resume_last_session=resumed url={}",
+                    redacted_url(&url)
+                ));
+                candidate.app_url = url;
+                return navigate_to_app(&window, &candidate).await;
+            }
+            Err(error) => append_startup_log_entry(&format!(
+                "resume_last_session=fallback url={} error={error}",
+                redacted_url(&url)
+            )),
+        }
+    }
+
+    let (config, _) = check_app_targets(&config, &client)
+        .await
+        .map_err(|error| reachability_command_error(&client, &config, error))?;
+    state.select_app_target(&config);
+    navigate_to_app(&window, &config).await
+}
+
+/// Runs the `APP_URL` checks from `load_runtime_config` plus a reachability probe against
+/// a candidate URL, using the loaded `ALLOWED_HOSTS`, guard and HTTP client. Nothing is
+/// saved and the live config is not touched.
+#[tauri::command]
+async fn validate_app_url(
+    state: State<'_, AppState>,
+    url: String,
+) -> Result<AppUrlValidation, String> {
+    let (config, client) = get_config_with_client(&state)?;
+    let mut checks = Vec::new();
+    let valid = match check_candidate_app_url(&config, url.trim(), &mut checks) {
+        Some(candidate) => {
+            let probe = check_server_reachable(&candidate, &client).await;
+            record_app_url_check(&mut checks, "reachability", probe).is_some()
+        }
+        None => false,
+    };
+    append_startup_log_entry(&format!(
+        "validate_app_url valid={valid} checks={}",
+        checks.len()
+    ));
+    Ok(AppUrlValidation { valid, checks })
+}
+
+fn check_candidate_app_url(
+    config: &RuntimeConfig,
+    raw: &str,
+    checks: &mut Vec<AppUrlCheck>,
+) -> Option<RuntimeConfig> {
+    let locale = config.locale;
+    let mut url = record_app_url_check(
+        checks,
+        "parse",
+        Url::parse(raw).map_err(|error| {
+            tr(locale, "config.app_url_invalid").replace("{error}", &error.to_string())
+        }),
+    )?;
+    record_app_url_check(checks, "scheme", check_app_url_scheme(&url, locale))?;
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
+    let host = record_app_url_check(checks, "host", check_app_url_host(&url, locale))?;
+    record_app_url_check(
+        checks,
+        "allowed_hosts",
+        check_app_url_allowed(&host, &url, &config.allowed_hosts, locale),
+    )?;
+    record_app_url_check(
+        checks,
+        "release_localhost",
+        check_release_localhost_guard(&host, config.release_guard_hosts.as_ref()),
+    )?;
+
+    let reachability_url = match &config.health_check_path {
+        Some(path) => {
+            record_app_url_check(checks, "health_check_path", health_check_url(&url, path))?
+        }
+        None => url.clone(),
+    };
+    let mut candidate = config.clone();
+    candidate.app_url = url;
+    candidate.reachability_url = reachability_url;
+    Some(candidate)
+}
+
+fn record_app_url_check<T>(
+    checks: &mut Vec<AppUrlCheck>,
+    name: &'static str,
+    result: Result<T, String>,
+) -> Option<T> {
+    checks.push(AppUrlCheck {
+        name,
+        passed: result.is_ok(),
+        message: result.as_ref().err().cloned(),
+    });
+    result.ok()
+}
+
+#[derive(Serialize)]
+struct UrlAllowed {
+    allowed: bool,
+    reason: Option<NavigationBlock>,
+    external: bool,
+}
+
+/// Answers what `on_navigation` would do with `url` right now, using the live config and
+/// the hosts already seen over https. Nothing is logged or recorded.
+#[tauri::command]
+fn is_url_allowed(state: State<'_, AppState>, url: String) -> Result<UrlAllowed, String> {
+    let config = get_config(&state)?;
+    let url = Url::parse(url.trim()).map_err(|error| error.to_string())?;
+    let https_seen = url.scheme() == "http"
+        && !config.allow_https_downgrade
+        && url
+            .host_str()
+            .map(normalize_host)
+            .is_some_and(|host| state.is_https_seen_host(&host));
+    let result = if is_blocked_downgrade(config.strict_https, &config.app_url, &url) || https_seen {
+        Err(NavigationBlock::Downgrade)
+    } else {
+        is_allowed_navigation(
+            &url,
+            &config.allowed_hosts,
+            config.strict_allowed_hosts,
+            config.block_data_urls,
+        )
+    };
+    let reason = result.err();
+    Ok(UrlAllowed {
+        allowed: reason.is_none(),
+        reason,
+        external: reason.is_some_and(|block| block != NavigationBlock::Downgrade)
+            && config.external_schemes.contains(url.scheme()),
     })
 }
 
 #[tauri::command]
-async fn launch_app(window: Window, state: State<'_, AppState>) -> Result<(), String> {
+async fn test_connection(state: State<'_, AppState>) -> Result<ConnectionTestResult, String> {
+    let (config, client) = get_config_with_client(&state)?;
+    let result = match check_server_reachable(&config, &client).await {
+        Ok(probe) => ConnectionTestResult {
+            reachable: true,
+            latency_ms: Some(probe.latency_ms),
+            status: Some(probe.status),
+            error: None,
+        },
+        Err(error) => ConnectionTestResult {
+            reachable: false,
+            latency_ms: None,
+            status: None,
+            error: Some(error),
+        },
+    };
+    append_startup_log_entry(&format!(
+        "test_connection reachable={} status={} latency_ms={}",
+        result.reachable,
+        result
+            .status
+            .map_or_else(|| "-".to_string(), |status| status.to_string()),
+        result
+            .latency_ms
+            .map_or_else(|| "-".to_string(), |latency| latency.to_string())
+    ));
+    Ok(result)
+}
+
+/// Fetches `SERVER_INFO_PATH` from the selected `APP_URL` origin with the reachability
+/// client, so its timeouts, proxy, pins and headers apply.
+#[tauri::command]
+async fn get_server_info(state: State<'_, AppState>) -> Result<ServerInfo, String> {
+    let (config, client) = get_config_with_client(&state)?;
+    let url = server_info_url(&config.app_url, &config.server_info_path)?;
+    let request = with_webview_headers(client.http.get(url.clone()), &config, &url);
+    let response = with_reachability_auth(request, &config, &url)
+        .header(
+            reqwest::header::ACCEPT,
+            "application/json, text/plain;q=0.9",
+        )
+        .send()
+        .await
+        .map_err(|error| format!("Could not fetch server info at {url}: {error}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!(
+            "Server info at {url} returned HTTP {}.",
+            status.as_u16()
+        ));
+    }
+    let body = response
+        .text()
+        .await
+        .map_err(|error| format!("Could not read server info at {url}: {error}"))?;
+    let json = serde_json::from_str::<serde_json::Value>(&body).ok();
+    let text = json.is_none().then(|| {
+        body.trim()
+            .chars()
+            .take(SERVER_INFO_MAX_TEXT_CHARS)
+            .collect()
+    });
+    Ok(ServerInfo {
+        url: url.to_string(),
+        status: status.as_u16(),
+        json,
+        text,
+    })
+}
+
+#[tauri::command]
+async fn get_dns_info(state: State<'_, AppState>) -> Result<DnsInfo, String> {
+    let config = get_config(&state)?;
+    tauri::async_runtime::spawn_blocking(move || resolve_app_host(&config.app_url))
+        .await
+        .map_err(|error| format!("DNS lookup task failed: {error}"))
+}
+
+/// Resolves the `APP_URL` host with the system resolver and logs the outcome, so a DNS
+/// failure can be told apart from a refused connection.
+fn resolve_app_host(app_url: &Url) -> DnsInfo {
+    let host = app_url.host_str().unwrap_or_default().to_string();
+    let info = match app_url.socket_addrs(|| None) {
+        Ok(addrs) => {
+            let mut addresses: Vec<String> =
+                addrs.iter().map(|addr| addr.ip().to_string()).collect();
+            addresses.dedup();
+            DnsInfo {
+                host,
+                addresses,
+                error: None,
+            }
+        }
+        Err(error) => DnsInfo {
+            host,
+            addresses: Vec::new(),
+            error: Some(error.to_string()),
+        },
+    };
+    match &info.error {
+        Some(error) => {
+            append_startup_log_entry(&format!("dns app_host={} error={error}", info.host))
+        }
+        None => append_startup_log_entry(&format!(
+            "dns app_host={} addrs={}",
+            info.host,
+            info.addresses.join(",")
+        )),
+    }
+    info
+}
+
+#[tauri::command]
+fn show_offline_page(
+    window: Window,
+    state: State<'_, AppState>,
+    error: Option<String>,
+) -> Result<(), String> {
     let config = get_config(&state)?;
-    check_server_reachable(&config.app_url).await?;
-    let build_parity = check_web_build_parity(&config).await;
+    render_offline_page(&window, &config, error.as_deref())
+}
+
+fn render_offline_page(
+    window: &Window,
+    config: &RuntimeConfig,
+    error: Option<&str>,
+) -> Result<(), String> {
+    let host = config.app_url.host_str().unwrap_or_default();
+    let locale = config.locale;
+    let html = OFFLINE_HTML
+        .replace("{{lang}}", locale.code())
+        .replace("{{heading}}", &escape_html(tr(locale, "offline.heading")))
+        .replace(
+            "{{could_not_connect}}",
+            &escape_html(&tr(locale, "offline.could_not_connect").replace("{host}", host)),
+        )
+        .replace("{{retry}}", &escape_html(tr(locale, "offline.retry")))
+        .replace("{{retrying}}", &escape_html(tr(locale, "offline.retrying")))
+        .replace(
+            "{{retry_progress}}",
+            &escape_html(tr(locale, "offline.retry_progress")),
+        )
+        .replace("{{title}}", &escape_html(&render_window_title(config)))
+        .replace(
+            "{{background}}",
+            config
+                .window_background
+                .as_deref()
+                .unwrap_or(DEFAULT_PAGE_BACKGROUND),
+        )
+        .replace(
+            "{{error}}",
+            &escape_html(error.unwrap_or(tr(locale, "offline.no_response"))),
+        );
+    // Rewrite the current document instead of navigating so the page needs no network
+    // and keeps the Tauri bridge of the page it replaces.
+    let html = serde_json::to_string(&html)
+        .map_err(|error| format!("Failed to encode offline page: {error}"))?;
+    window
+        .eval(&format!(
+            "document.open();document.write({html});document.close();"
+        ))
+        .map_err(|error| format!("Failed to show offline page: {error}"))?;
+    append_startup_log_entry(&format!("offline_page=shown host={host}"));
+    window
+        .show()
+        .map_err(|error| format!("Failed to show main window: {error}"))
+}
+
+#[tauri::command]
+async fn retry_connect(window: Window, state: State<'_, AppState>) -> Result<(), CommandError> {
+    let (config, client) = get_config_with_client(&state)?;
+    let reachability = check_server_reachable_with_backoff(&window, &config, &client).await;
+    if let Some(mut result) = state.last_bootstrap_result() {
+        result.reachable = reachability.is_ok();
+        result.reachability_error = reachability.as_ref().err().cloned();
+        if let Ok((selected, _)) = &reachability {
+            result.app_url = Some(selected.app_url.to_string());
+            result.app_host = selected.app_url.host_str().map(ToString::to_string);
+        }
+        result.reachability_latency_ms = reachability
+            .as_ref()
+            .ok()
+            .map(|(_, probe)| probe.latency_ms);
+        result.reachability_status = reachability.as_ref().ok().map(|(_, probe)| probe.status);
+        result.content_type_warning = reachability
+            .as_ref()
+            .ok()
+            .and_then(|(_, probe)| probe.content_type_warning.clone());
+        let maintenance = reachability
+            .is_err()
+            .then(|| client.maintenance(&config.reachability_url))
+            .flatten();
+        result.maintenance = maintenance.is_some();
+        result.retry_after_secs = maintenance.and_then(|window| window.retry_after_secs);
+        if maintenance.is_some() {
+            result.error_code = Some("maintenance");
+        } else if reachability.is_err() {
+            result.error_code = Some("unreachable");
+        } else if matches!(result.error_code, Some("unreachable" | "maintenance")) {
+            result.error_code = None;
+        }
+        state.record_bootstrap_result(&result);
+    }
+    let (config, _) =
+        reachability.map_err(|error| reachability_command_error(&client, &config, error))?;
+    state.select_app_target(&config);
+    navigate_to_app(&window, &config).await
+}
+
+async fn navigate_to_app(window: &Window, config: &RuntimeConfig) -> Result<(), CommandError> {
+    // Same decision on_navigation would make; failing here reports it instead of a page
+    // that silently never loads.
+    if is_allowed_navigation(
+        &config.app_url,
+        &config.allowed_hosts,
+        config.strict_allowed_hosts,
+        config.block_data_urls,
+    )
+    .is_err()
+    {
+        return Err(CommandError::NavigationBlocked(format!(
+            "{} is not in ALLOWED_HOSTS.",
+            redacted_url(&config.app_url)
+        )));
+    }
+    let build_parity = check_web_build_parity(config).await;
     if !build_parity.parity_ok && config.enforce_web_build {
-        return Err(build_parity.parity_error.unwrap_or_else(|| {
-            "Server build does not satisfy required minimum build hash.".to_string()
-        }));
+        return Err(CommandError::BuildParity(
+            build_parity.parity_error.unwrap_or_else(|| {
+                "Server build does not satisfy required minimum build hash.".to_string()
+            }),
+        ));
     }
     let target = config
         .app_url
@@ -260,664 +2184,5023 @@ async fn launch_app(window: Window, state: State<'_, AppState>) -> Result<(), St
 
     window
         .eval(&format!("window.location.replace(\"{}\");", target))
-        .map_err(|error| format!("Failed to navigate to APP_URL: {error}"))?;
+        .map_err(|error| CommandError::Window(format!("Failed to navigate to APP_URL: {error}")))?;
 
     window
         .show()
-        .map_err(|error| format!("Failed to show main window: {error}"))?;
+        .map_err(|error| CommandError::Window(format!("Failed to show main window: {error}")))?;
     let _ = window.set_focus();
+    close_splash(window);
 
     Ok(())
 }
 
 #[tauri::command]
-async fn retry_connect(window: Window, state: State<'_, AppState>) -> Result<(), String> {
-    launch_app(window, state).await
+fn get_zoom(state: State<'_, AppState>) -> f64 {
+    state.zoom_factor()
 }
 
 #[tauri::command]
-fn show_main_window(window: Window) -> Result<(), String> {
-    window
-        .show()
-        .map_err(|error| format!("Failed to show main window: {error}"))?;
-    let _ = window.set_focus();
-    Ok(())
+fn zoom_in(window: Window, state: State<'_, AppState>) -> Result<f64, String> {
+    apply_zoom(&window, &state, state.zoom_factor() + WINDOW_ZOOM_STEP)
 }
 
 #[tauri::command]
-async fn get_about_info(state: State<'_, AppState>) -> Result<AboutInfo, String> {
-    if let Some(config) = &state.config {
-        let parity = check_web_build_parity(config).await;
-        return Ok(AboutInfo {
-            title: config.window_title.clone(),
-            version: env!("CARGO_PKG_VERSION").to_string(),
-            app_host: config
-                .app_url
-                .host_str()
-                .unwrap_or("unknown-host")
-                .to_string(),
-            app_url: config.app_url.to_string(),
-            required_web_build_hash: config.min_web_build_hash.clone(),
-            enforce_web_build: config.enforce_web_build,
-            web_build_hash: parity.web_build_hash,
-            web_build_time: parity.web_build_time,
-            web_build_error: parity.parity_error,
-        });
-    }
+fn zoom_out(window: Window, state: State<'_, AppState>) -> Result<f64, String> {
+    apply_zoom(&window, &state, state.zoom_factor() - WINDOW_ZOOM_STEP)
+}
 
-    Ok(AboutInfo {
-        title: DEFAULT_TITLE.to_string(),
-        version: env!("CARGO_PKG_VERSION").to_string(),
-        app_host: "not-configured".to_string(),
-        app_url: "not-configured".to_string(),
-        required_web_build_hash: None,
-        enforce_web_build: false,
-        web_build_hash: None,
-        web_build_time: None,
-        web_build_error: None,
-    })
+/// Returns to `WINDOW_ZOOM`, not 100%, so a deployment-wide default survives a reset.
+#[tauri::command]
+fn reset_zoom(window: Window, state: State<'_, AppState>) -> Result<f64, String> {
+    let default = get_config(&state).map_or(DEFAULT_WINDOW_ZOOM, |config| config.window_zoom);
+    apply_zoom(&window, &state, default)
 }
 
-fn get_config(state: &AppState) -> Result<RuntimeConfig, String> {
-    state.config.as_ref().cloned().ok_or_else(|| {
-        state
-            .config_error
-            .clone()
-            .unwrap_or_else(|| "Runtime configuration missing.".to_string())
-    })
+fn apply_zoom(window: &Window, state: &AppState, factor: f64) -> Result<f64, String> {
+    let factor = clamp_zoom(factor);
+    window
+        .eval(&format!(
+            "document.documentElement.style.zoom = '{factor}';"
+        ))
+        .map_err(|error| format!("Failed to apply zoom: {error}"))?;
+    state.set_zoom_factor(factor);
+    append_startup_log_entry(&format!("window_zoom_changed factor={factor}"));
+    Ok(factor)
 }
 
-async fn check_server_reachable(url: &Url) -> Result<(), String> {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(8))
-        .redirect(reqwest::redirect::Policy::limited(5))
-        .build()
-        .map_err(|error| format!("HTTP client init failed: {error}"))?;
+#[derive(Serialize)]
+struct InputRestrictions {
+    disable_context_menu: bool,
+    disable_text_selection: bool,
+}
 
-    let response = client
-        .get(url.clone())
-        .send()
-        .await
-        .map_err(|error| format!("Could not reach server at {url}: {error}"))?;
+#[tauri::command]
+fn get_input_restrictions(state: State<'_, AppState>) -> InputRestrictions {
+    let config = state.snapshot().config;
+    InputRestrictions {
+        disable_context_menu: config
+            .as_ref()
+            .is_some_and(|config| config.disable_context_menu),
+        disable_text_selection: config
+            .as_ref()
+            .is_some_and(|config| config.disable_text_selection),
+    }
+}
 
-    let status = response.status();
-    if status.is_success()
-        || status.is_redirection()
-        || status.as_u16() == 401
-        || status.as_u16() == 403
-    {
+#[tauri::command]
+fn get_inactivity_reset_secs(state: State<'_, AppState>) -> Option<u64> {
+    state
+        .snapshot()
+        .config
+        .and_then(|config| config.inactivity_reset)
+        .map(|value| value.as_secs())
+}
+
+/// Sends an idle kiosk back to `APP_URL`. Pages outside the remote app, such as the
+/// bootstrap and offline screens, are left alone.
+#[tauri::command]
+fn reset_to_home(window: Window, state: State<'_, AppState>) -> Result<(), String> {
+    let config = get_config(&state)?;
+    let current_url = window.url();
+    if !matches!(current_url.scheme(), "http" | "https") {
         return Ok(());
     }
-
-    Err(format!(
-        "Server responded with status {} when requesting {}",
-        status, url
-    ))
+    append_startup_log_entry(&format!(
+        "inactivity_reset timestamp={} from={current_url}",
+        current_timestamp()
+    ));
+    let target = serde_json::to_string(config.app_url.as_str())
+        .map_err(|error| format!("Failed to encode APP_URL: {error}"))?;
+    window
+        .eval(&format!("window.location.replace({target});"))
+        .map_err(|error| format!("Failed to navigate to APP_URL: {error}"))
 }
 
-fn normalized_hash(value: &str) -> String {
-    value
-        .chars()
-        .filter(|c| c.is_ascii_hexdigit())
-        .collect::<String>()
-        .to_ascii_lowercase()
+/// The concrete `ALLOWED_HOSTS` entries offered by the Ctrl+Shift+P host switcher.
+/// Wildcard and suffix entries name no single host, so they are left out.
+#[tauri::command]
+fn list_allowed_hosts(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let config = get_config(&state)?;
+    Ok(sorted_entries(&config.allowed_hosts)
+        .into_iter()
+        .filter(|entry| {
+            let (host, _) = split_host_port(entry);
+            host != "*" && !host.starts_with('.')
+        })
+        .collect())
 }
 
-fn deploy_info_url(app_url: &Url) -> Result<Url, String> {
-    let mut deploy_url = app_url.clone();
-    deploy_url
-        .set_path("/api/admin/deploy-info");
-    deploy_url.set_query(None);
-    deploy_url.set_fragment(None);
-    Ok(deploy_url)
+/// Opens `https://<host>/` for the host switcher. The URL goes through the candidate
+/// `APP_URL` checks and the reachability check first; the live config is not changed, so
+/// Home and Retry still go to `APP_URL`.
+#[tauri::command]
+async fn navigate_to_host(
+    window: Window,
+    state: State<'_, AppState>,
+    host: String,
+) -> Result<(), CommandError> {
+    let (config, client) = get_config_with_client(&state)?;
+    let (bare_host, port) = split_host_port(host.trim());
+    let authority = if bare_host.contains(':') {
+        format!("[{bare_host}]")
+    } else {
+        bare_host.to_string()
+    };
+    let raw = match port {
+        Some(port) => format!("https://{authority}:{port}/"),
+        None => format!("https://{authority}/"),
+    };
+    let mut checks = Vec::new();
+    let Some(candidate) = check_candidate_app_url(&config, &raw, &mut checks) else {
+        let message = checks
+            .into_iter()
+            .find_map(|check| check.message)
+            .unwrap_or_else(|| format!("{raw} is not an allowed host."));
+        append_startup_log_entry(&format!("navigate_to_host url={raw} result=rejected"));
+        return Err(CommandError::NavigationBlocked(message));
+    };
+    if let Err(error) = check_server_reachable(&candidate, &client).await {
+        append_startup_log_entry(&format!("navigate_to_host url={raw} result=unreachable"));
+        return Err(reachability_command_error(&client, &candidate, error));
+    }
+    append_startup_log_entry(&format!("navigate_to_host url={raw} result=ok"));
+    navigate_to_app(&window, &candidate).await
 }
 
-async fn fetch_deploy_info(app_url: &Url) -> Result<(String, Option<String>), String> {
-    let deploy_url = deploy_info_url(app_url)?;
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(8))
-        .redirect(reqwest::redirect::Policy::limited(5))
-        .build()
-        .map_err(|error| format!("HTTP client init failed: {error}"))?;
+#[tauri::command]
+fn reload_page(window: Window, state: State<'_, AppState>) -> Result<(), String> {
+    let config = get_config(&state)?;
+    reload_window(&window, &config)
+}
 
-    let response = client
-        .get(deploy_url.clone())
-        .send()
-        .await
-        .map_err(|error| format!("Could not fetch deploy info at {deploy_url}: {error}"))?;
+fn reload_window(window: &Window, config: &RuntimeConfig) -> Result<(), String> {
+    let current_url = window.url();
+    let current_host = current_url.host_str().map(normalize_host);
+    let allowed = matches!(current_url.scheme(), "http" | "https")
+        && current_host
+            .as_deref()
+            .map(|host| {
+                is_host_allowed(
+                    host,
+                    current_url.port_or_known_default(),
+                    &config.allowed_hosts,
+                )
+            })
+            .unwrap_or(false);
 
-    if !response.status().is_success() {
+    if !allowed {
         return Err(format!(
-            "Deploy info endpoint returned status {} at {}",
-            response.status(),
-            deploy_url
+            "Reload refused: current page {current_url} is not on an allowed host."
         ));
     }
 
-    let payload = response
-        .json::<DeployInfoResponse>()
-        .await
-        .map_err(|error| format!("Invalid deploy info response: {error}"))?;
-
-    let hash = payload
-        .build
-        .as_ref()
-        .and_then(|value| value.hash.clone())
-        .or_else(|| payload.git.as_ref().and_then(|value| value.hash.clone()))
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
-        .ok_or_else(|| "Deploy info does not include a build or git hash.".to_string())?;
-
-    let build_time = payload
-        .build
-        .as_ref()
-        .and_then(|value| value.built_at.clone())
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty());
+    window
+        .eval("window.location.reload();")
+        .map_err(|error| format!("Failed to reload page: {error}"))
+}
 
-    Ok((hash, build_time))
+#[tauri::command]
+fn show_main_window(window: Window) -> Result<(), String> {
+    window
+        .show()
+        .map_err(|error| format!("Failed to show main window: {error}"))?;
+    let _ = window.set_focus();
+    close_splash(&window);
+    Ok(())
 }
 
-async fn check_web_build_parity(config: &RuntimeConfig) -> BuildParityResult {
-    let Some(required_raw) = config.min_web_build_hash.clone() else {
-        return BuildParityResult {
-            web_build_hash: None,
-            web_build_time: None,
-            parity_ok: true,
-            parity_error: None,
-        };
+/// Replaces any running watchdog with one for the current config's
+/// `REACHABILITY_POLL_SECS`; with the key unset the old task just stops.
+fn restart_reachability_watchdog(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let generation = state.watchdog_generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let config = state.snapshot().config;
+    // Drops an offline suffix left by the previous watchdog and picks up a new title.
+    if let (Some(window), Some(config)) = (app.get_window("main"), config.as_ref()) {
+        set_window_title(&window, config, false);
+    }
+    let Some(interval) = config.and_then(|config| config.reachability_poll) else {
+        return;
     };
+    append_startup_log_entry(&format!(
+        "reachability_watchdog=started interval_secs={}",
+        interval.as_secs()
+    ));
+    tauri::async_runtime::spawn(run_reachability_watchdog(app.clone(), generation, interval));
+}
 
-    let required = normalized_hash(&required_raw);
-    if required.is_empty() {
-        return BuildParityResult {
-            web_build_hash: None,
-            web_build_time: None,
-            parity_ok: true,
-            parity_error: None,
+/// Emits `reachability-changed` when a periodic check disagrees with the previous one.
+/// The first check only sets the baseline unless bootstrap has already reported one.
+/// The main window title carries `OFFLINE_TITLE_SUFFIX` whenever a check fails.
+async fn run_reachability_watchdog(app: AppHandle, generation: u64, interval: Duration) {
+    let mut reachable = app
+        .state::<AppState>()
+        .last_bootstrap_result()
+        .map(|result| result.reachable);
+    let mut title_offline = false;
+    loop {
+        tokio::time::sleep(interval).await;
+        let state = app.state::<AppState>();
+        // Exit after a config change or once the main window is gone at shutdown.
+        if state.watchdog_generation.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let Some(window) = app.get_window("main") else {
+            return;
         };
-    }
-
-    match fetch_deploy_info(&config.app_url).await {
-        Ok((web_hash_raw, web_build_time)) => {
-            let web_hash = normalized_hash(&web_hash_raw);
-            let parity_ok = web_hash.starts_with(&required);
-            let parity_error = if parity_ok {
-                None
-            } else {
-                Some(format!(
-                    "Server build hash '{}' does not match required minimum hash '{}'.",
-                    web_hash_raw, required_raw
-                ))
-            };
+        let Ok((config, client)) = get_config_with_client(&state) else {
+            return;
+        };
+        let result = check_server_reachable(&config, &client).await;
+        if state.watchdog_generation.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let now_reachable = result.is_ok();
+        if title_offline == now_reachable {
+            title_offline = !now_reachable;
+            set_window_title(&window, &config, title_offline);
+        }
+        let changed = reachable.is_some_and(|previous| previous != now_reachable);
+        reachable = Some(now_reachable);
+        if !changed {
+            continue;
+        }
 
-            BuildParityResult {
-                web_build_hash: Some(web_hash_raw),
-                web_build_time,
-                parity_ok,
-                parity_error,
-            }
+        let payload = ReachabilityChanged {
+            reachable: now_reachable,
+            status: result.as_ref().ok().map(|probe| probe.status),
+            latency_ms: result.as_ref().ok().map(|probe| probe.latency_ms),
+            error: result.err(),
+        };
+        append_startup_log_entry(&format!(
+            "reachability_changed timestamp={} reachable={} error={}",
+            current_timestamp(),
+            payload.reachable,
+            payload.error.as_deref().unwrap_or("-")
+        ));
+        if let Err(error) = app.emit_all(REACHABILITY_CHANGED_EVENT, payload) {
+            append_startup_log_entry(&format!("reachability_changed_emit=error:{error}"));
         }
-        Err(error) => BuildParityResult {
-            web_build_hash: None,
-            web_build_time: None,
-            parity_ok: !config.enforce_web_build,
-            parity_error: Some(format!("Could not verify server build hash: {error}")),
-        },
     }
 }
 
-fn normalize_host(value: &str) -> String {
-    value.trim().to_ascii_lowercase()
+/// Hard ceiling on the first bootstrap: if it has not reported back by then, show the main
+/// window with the offline page (or the bootstrap screen on a config error) instead of
+/// leaving the user on the splash.
+async fn enforce_bootstrap_deadline(window: Window, deadline: Duration) {
+    tokio::time::sleep(deadline).await;
+    let state = window.state::<AppState>();
+    if state.last_bootstrap_result().is_some() {
+        return;
+    }
+    append_startup_log_entry(&format!(
+        "bootstrap_timeout=forced after_secs={}",
+        deadline.as_secs()
+    ));
+    if let Ok(config) = get_config(&state) {
+        let error = format!(
+            "Startup did not finish within {} seconds.",
+            deadline.as_secs()
+        );
+        if let Err(error) = render_offline_page(&window, &config, Some(&error)) {
+            append_startup_log_entry(&format!("bootstrap_timeout_page=error:{error}"));
+        }
+    }
+    let _ = window.show();
+    let _ = window.set_focus();
+    close_splash(&window);
 }
 
-fn current_timestamp() -> String {
-    match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(duration) => duration.as_secs().to_string(),
-        Err(_) => "0".to_string(),
+fn close_splash(window: &Window) {
+    if let Some(splash) = window.get_window("splash") {
+        let _ = splash.close();
     }
 }
 
-fn appdata_logs_dir_path() -> Option<PathBuf> {
-    std::env::var("APPDATA")
-        .ok()
-        .map(|app_data| PathBuf::from(app_data).join("CRA Client").join("logs"))
+/// Substitutes `{host}`, `{app_url}` and `{version}` in `WINDOW_TITLE`. Any other `{...}`
+/// text is left as written.
+fn render_window_title(config: &RuntimeConfig) -> String {
+    config
+        .window_title
+        .replace("{host}", config.app_url.host_str().unwrap_or_default())
+        .replace("{app_url}", config.app_url.as_str())
+        .replace("{version}", env!("CARGO_PKG_VERSION"))
 }
 
-fn startup_log_path() -> Option<PathBuf> {
-    appdata_logs_dir_path().map(|path| path.join("startup.log"))
+fn set_window_title(window: &Window, config: &RuntimeConfig, offline: bool) {
+    let title = render_window_title(config);
+    let title = if offline {
+        format!("{title} {}", config.offline_title_suffix)
+    } else {
+        title
+    };
+    if let Err(error) = window.set_title(&title) {
+        append_startup_log_entry(&format!("window_title=error:{error}"));
+    }
 }
 
-fn append_startup_log_entry(message: &str) {
-    let Some(log_path) = startup_log_path() else {
-        return;
-    };
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
 
-    if let Some(parent) = log_path.parent() {
-        if fs::create_dir_all(parent).is_err() {
+const OFFLINE_HTML: &str = r#"<!doctype html>
+<html lang="{{lang}}">
+  <head>
+    <meta charset="UTF-8" />
+    <title>{{title}}</title>
+    <style>
+      html, body {
+        margin: 0;
+        height: 100%;
+        font-family: "Segoe UI", Tahoma, sans-serif;
+        background: {{background}};
+        color: #f7fbff;
+      }
+      main {
+        height: 100%;
+        display: grid;
+        place-content: center;
+        justify-items: center;
+        gap: 12px;
+        padding: 20px;
+        text-align: center;
+      }
+      h1 { margin: 0; font-size: 1.2rem; }
+      p { margin: 0; color: #a7b6c9; font-size: 0.9rem; }
+      pre {
+        margin: 0;
+        max-width: 520px;
+        color: #ffd6d6;
+        font-size: 0.82rem;
+        white-space: pre-wrap;
+        overflow-wrap: anywhere;
+      }
+      button {
+        border: 1px solid #2e76c0;
+        background: #2e76c0;
+        color: #fff;
+        border-radius: 9px;
+        font-size: 0.86rem;
+        padding: 8px 12px;
+        cursor: pointer;
+      }
+      button[disabled] { opacity: 0.55; cursor: not-allowed; }
+    </style>
+  </head>
+  <body>
+    <main>
+      <h1>{{heading}}</h1>
+      <p>{{could_not_connect}}</p>
+      <pre id="error">{{error}}</pre>
+      <button id="retry" type="button" data-retrying="{{retrying}}" data-progress="{{retry_progress}}">{{retry}}</button>
+    </main>
+    <script>
+      (() => {
+        const retry = document.getElementById('retry');
+        const error = document.getElementById('error');
+        retry.addEventListener('click', () => {
+          const tauriObj = window.__TAURI__;
+          const invoke = tauriObj?.invoke ?? tauriObj?.core?.invoke;
+          if (!invoke) {
+            error.textContent = 'Tauri invoke bridge unavailable';
             return;
-        }
-    }
+          }
+          retry.disabled = true;
+          error.textContent = retry.dataset.retrying;
+          const unlisten = tauriObj?.event?.listen?.('retry-progress', ({ payload }) => {
+            if (payload.next_delay_ms != null) {
+              const seconds = Math.ceil(payload.next_delay_ms / 1000);
+              error.textContent = retry.dataset.progress
+                .replace('{attempt}', payload.attempt)
+                .replace('{total}', payload.total)
+                .replace('{seconds}', seconds);
+            }
+          });
+          invoke('retry_connect')
+            .catch((reason) => {
+              error.textContent = reason?.message ?? String(reason);
+              retry.disabled = false;
+            })
+            .finally(() => {
+              void unlisten?.then((stop) => stop());
+            });
+        });
+      })();
+    </script>
+  </body>
+</html>
+"#;
 
-    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(log_path) {
-        let _ = writeln!(file, "{message}");
-    }
+/// Fixed at window creation, so WEBVIEW_HEADERS and ALLOWED_HOSTS changes apply to it
+/// after a restart.
+fn webview_headers_script(config: &RuntimeConfig) -> String {
+    let headers: Vec<(&str, String)> = config
+        .webview_headers
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.as_str(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect();
+    WEBVIEW_HEADERS_SCRIPT
+        .replace(
+            "{{headers}}",
+            &serde_json::to_string(&headers).unwrap_or_else(|_| "[]".to_string()),
+        )
+        .replace(
+            "{{allowed_hosts}}",
+            &serde_json::to_string(&sorted_entries(&config.allowed_hosts))
+                .unwrap_or_else(|_| "[]".to_string()),
+        )
 }
 
-fn read_process_env_value(key: &str) -> Option<String> {
-    std::env::var(key).ok().and_then(|value| {
-        let trimmed = value.trim();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed.to_string())
-        }
-    })
+fn splash_url(title: &str, background: Option<&str>) -> Option<Url> {
+    let html = SPLASH_HTML
+        .replace("{{title}}", &escape_html(title))
+        .replace(
+            "{{background}}",
+            background.unwrap_or(DEFAULT_PAGE_BACKGROUND),
+        );
+    Url::parse(&format!(
+        "data:text/html;charset=utf-8,{}",
+        utf8_percent_encode(&html, NON_ALPHANUMERIC)
+    ))
+    .ok()
 }
 
-fn read_file_value(key: &str, file_values: &HashMap<String, String>) -> Option<String> {
-    file_values
-        .get(key)
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
+#[tauri::command]
+async fn get_about_info(state: State<'_, AppState>) -> Result<AboutInfo, String> {
+    Ok(resolve_about_info(state.snapshot().config.as_ref()).await)
 }
 
-fn read_optional_value(
-    file_key: &str,
-    env_key: Option<&str>,
-    file_values: &HashMap<String, String>,
-) -> Option<(String, String)> {
-    if let Some(key) = env_key {
-        if let Some(value) = read_process_env_value(key) {
-            return Some((value, format!("process env {key}")));
-        }
+async fn resolve_about_info(config: Option<&RuntimeConfig>) -> AboutInfo {
+    if let Some(config) = config {
+        let parity = check_web_build_parity(config).await;
+        return AboutInfo {
+            title: render_window_title(config),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            app_host: config
+                .app_url
+                .host_str()
+                .unwrap_or("unknown-host")
+                .to_string(),
+            app_url: config.app_url.to_string(),
+            profile: config.profile.clone(),
+            locale: config.locale.code(),
+            required_web_build_hash: config.min_web_build_hash.clone(),
+            enforce_web_build: config.enforce_web_build,
+            web_build_hash: parity.web_build_hash,
+            web_build_time: parity.web_build_time,
+            web_build_error: parity.parity_error,
+        };
     }
 
-    read_file_value(file_key, file_values).map(|value| (value, format!("client.env {file_key}")))
+    AboutInfo {
+        title: DEFAULT_TITLE.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        app_host: "not-configured".to_string(),
+        app_url: "not-configured".to_string(),
+        profile: None,
+        locale: system_locale().code(),
+        required_web_build_hash: None,
+        enforce_web_build: false,
+        web_build_hash: None,
+        web_build_time: None,
+        web_build_error: None,
+    }
 }
 
-fn read_required_value(
-    file_key: &str,
-    env_key: Option<&str>,
-    file_values: &HashMap<String, String>,
-) -> Result<(String, String), String> {
-    read_optional_value(file_key, env_key, file_values).ok_or_else(|| {
-        format!(
-            "Missing required setting: {file_key}. Set it via {} or client.env.",
-            env_key.unwrap_or("environment variable")
-        )
-    })
-}
+#[tauri::command]
+async fn copy_diagnostics(app: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let snapshot = state.snapshot();
+    let about = resolve_about_info(snapshot.config.as_ref()).await;
+    let or_dash = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
 
-fn parse_bool_value(value: &str) -> Option<bool> {
-    match value.trim().to_ascii_lowercase().as_str() {
-        "1" | "true" | "yes" | "y" | "on" => Some(true),
-        "0" | "false" | "no" | "n" | "off" => Some(false),
-        _ => None,
-    }
+    // Only non-secret fields are included; auth headers and proxy credentials never reach
+    // AboutInfo or the diagnostics list.
+    let mut lines = vec![
+        "CRA Client diagnostics".to_string(),
+        format!("Title: {}", about.title),
+        format!("Version: {}", about.version),
+        format!("Target Host: {}", about.app_host),
+        format!("URL: {}", about.app_url),
+        format!("Profile: {}", or_dash(&about.profile)),
+        format!("Web Build Hash: {}", or_dash(&about.web_build_hash)),
+        format!("Web Build Time: {}", or_dash(&about.web_build_time)),
+        format!(
+            "Required Build Hash: {}",
+            or_dash(&about.required_web_build_hash)
+        ),
+        format!("Enforce Build Parity: {}", about.enforce_web_build),
+        format!("Build Check Error: {}", or_dash(&about.web_build_error)),
+        format!(
+            "Config Error: {}",
+            or_dash(&snapshot.config_error.as_ref().map(ToString::to_string))
+        ),
+        format!(
+            "OS: {} ({}, {})",
+            std::env::consts::OS,
+            std::env::consts::FAMILY,
+            std::env::consts::ARCH
+        ),
+        String::new(),
+        "Startup diagnostics:".to_string(),
+    ];
+    lines.extend(snapshot.diagnostics);
+    let text = lines.join("\n");
+
+    app.clipboard_manager()
+        .write_text(text.clone())
+        .map_err(|error| format!("Could not copy diagnostics to clipboard: {error}"))?;
+
+    Ok(text)
 }
 
-fn read_bool_value(
-    file_key: &str,
-    env_key: Option<&str>,
-    fallback: bool,
-    file_values: &HashMap<String, String>,
-) -> Result<(bool, String), String> {
-    let Some((raw, source)) = read_optional_value(file_key, env_key, file_values) else {
-        return Ok((fallback, format!("default {fallback}")));
-    };
+#[tauri::command]
+fn get_startup_diagnostics(state: State<'_, AppState>) -> Vec<String> {
+    // Diagnostics are built without secret values (auth headers, proxy credentials), so the
+    // stored list is safe to hand to the web UI as-is.
+    state.snapshot().diagnostics
+}
 
-    let Some(value) = parse_bool_value(&raw) else {
-        return Err(format!(
-            "{file_key} must be a boolean (true/false/1/0), got '{raw}'."
-        ));
+/// Writes a zip of the startup logs (all rotated generations), `bootstrap-result.json`,
+/// the redacted effective config, `window-state.json` and basic OS info to `path`, or to
+/// the Downloads folder when no path is given, and returns where it was written. Reads
+/// only local files, so it works while the server is unreachable.
+#[tauri::command]
+fn export_diagnostics_bundle(
+    state: State<'_, AppState>,
+    path: Option<String>,
+) -> Result<String, String> {
+    let snapshot = state.snapshot();
+    let target = match path.map(|value| value.trim().to_string()) {
+        Some(value) if !value.is_empty() => PathBuf::from(value),
+        _ => default_diagnostics_bundle_path()
+            .ok_or_else(|| "Could not resolve a folder for the diagnostics bundle.".to_string())?,
     };
 
-    Ok((value, source))
+    let mut entries: Vec<(String, Vec<u8>)> = Vec::new();
+    if let Some(log_path) = startup_log_path() {
+        let generations = std::iter::once(log_path.clone()).chain(
+            (1..=LOG_ROTATION_GENERATIONS)
+                .map(|generation| rotated_log_path(&log_path, generation)),
+        );
+        for file in generations {
+            if let (Ok(bytes), Some(name)) = (fs::read(&file), file.file_name()) {
+                entries.push((format!("logs/{}", name.to_string_lossy()), bytes));
+            }
+        }
+    }
+    for (name, file) in [
+        ("logs/bootstrap-result.json", bootstrap_result_path()),
+        ("window-state.json", appdata_window_state_path()),
+    ] {
+        if let Some(bytes) = file.and_then(|file| fs::read(file).ok()) {
+            entries.push((name.to_string(), bytes));
+        }
+    }
+
+    // effective_config_json already reduces secrets to whether they are set.
+    let config = serde_json::json!({
+        "config": snapshot.config.as_ref().map(effective_config_json),
+        "config_error": snapshot.config_error.as_ref().map(ConfigError::message),
+        "diagnostics": snapshot.diagnostics,
+    });
+    entries.push((
+        "effective-config.json".to_string(),
+        serde_json::to_vec_pretty(&config).unwrap_or_default(),
+    ));
+    let system = serde_json::json!({
+        "client_version": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "family": std::env::consts::FAMILY,
+        "arch": std::env::consts::ARCH,
+        "exported_at": current_timestamp(),
+    });
+    entries.push((
+        "system.json".to_string(),
+        serde_json::to_vec_pretty(&system).unwrap_or_default(),
+    ));
+
+    // Assembled in memory so a failed export never leaves a truncated zip behind.
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, bytes) in &entries {
+        writer
+            .start_file(name.as_str(), options)
+            .and_then(|()| writer.write_all(bytes).map_err(zip::result::ZipError::from))
+            .map_err(|error| format!("Could not add {name} to the diagnostics bundle: {error}"))?;
+    }
+    let bytes = writer
+        .finish()
+        .map_err(|error| format!("Could not finish the diagnostics bundle: {error}"))?
+        .into_inner();
+    fs::write(&target, bytes)
+        .map_err(|error| format!("Could not write '{}': {error}", target.display()))?;
+
+    append_startup_log_entry(&format!(
+        "diagnostics_bundle=exported path={} files={}",
+        target.display(),
+        entries.len()
+    ));
+    Ok(target.display().to_string())
 }
 
-fn parse_window_dimension(
-    file_key: &str,
-    env_key: Option<&str>,
-    fallback: f64,
-    file_values: &HashMap<String, String>,
-) -> Result<(f64, String), String> {
-    let Some((raw, source)) = read_optional_value(file_key, env_key, file_values) else {
-        return Ok((fallback, format!("default {fallback}")));
-    };
+fn default_diagnostics_bundle_path() -> Option<PathBuf> {
+    let name = format!(
+        "cra-client-diagnostics-{}.zip",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |value| value.as_secs())
+    );
+    std::env::var("USERPROFILE")
+        .ok()
+        .map(|profile| PathBuf::from(profile).join("Downloads"))
+        .filter(|downloads| downloads.is_dir())
+        .or_else(appdata_logs_dir_path)
+        .map(|folder| folder.join(name))
+}
 
-    raw.parse::<f64>()
-        .map(|value| (value, source))
-        .map_err(|_| format!("{file_key} must be numeric, got '{raw}'."))
+#[tauri::command]
+fn get_effective_config(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
+    let config = get_config(&state)?;
+    Ok(effective_config_json(&config))
 }
 
-fn candidate_client_env_files() -> Vec<PathBuf> {
-    let mut files = Vec::new();
+/// Secret-bearing values (auth header value, URL credentials) are reduced to whether
+/// they are configured.
+fn effective_config_json(config: &RuntimeConfig) -> serde_json::Value {
+    serde_json::json!({
+        "profile": config.profile,
+        "data_dir": appdata_dir_path().map(|path| path.display().to_string()),
+        "locale": config.locale.code(),
+        "app_url": redacted_url(&config.app_url),
+        "reachability_url": redacted_url(&config.reachability_url),
+        "server_info_path": config.server_info_path,
+        "allowed_hosts": sorted_entries(&config.allowed_hosts),
+        "allowed_hosts_url": config.allowed_hosts_url.as_ref().map(redacted_url),
+        "window": {
+            "title": config.window_title,
+            "width": config.window_width_percent.map_or_else(
+                || serde_json::json!(config.window_width),
+                |percent| serde_json::json!(format!("{percent}%")),
+            ),
+            "height": config.window_height_percent.map_or_else(
+                || serde_json::json!(config.window_height),
+                |percent| serde_json::json!(format!("{percent}%")),
+            ),
+            "size_unit": config.window_size_unit.as_str(),
+            "min_width": config.window_min_width,
+            "min_height": config.window_min_height,
+            "max_width": config.window_max_width,
+            "max_height": config.window_max_height,
+            "zoom": config.window_zoom,
+            "icon_path": config.window_icon_path.as_ref().map(|path| path.display().to_string()),
+            "background": config.window_background,
+        },
+        "min_web_build_hash": config.min_web_build_hash,
+        "enforce_web_build": config.enforce_web_build,
+        "reachability_timeout_secs": config.reachability_timeout.as_secs(),
+        "reachability_connect_timeout_secs": config.reachability_connect_timeout.as_secs(),
+        "reachability_max_redirects": config.reachability_max_redirects,
+        "reachability_method": if config.reachability_head { "HEAD" } else { "GET" },
+        "retry_on_gateway_errors": config.retry_on_gateway_errors,
+        "health_expect_json_field": config
+            .health_expect_json_field
+            .as_ref()
+            .map(|(field, expected)| format!("{field}={expected}")),
+        "retry_attempts": config.retry_attempts,
+        "startup_grace_secs": config.startup_grace.as_secs(),
+        "bootstrap_max_secs": config.bootstrap_max.as_secs(),
+        "inactivity_reset_secs": config.inactivity_reset.map(|value| value.as_secs()),
+        "reachability_poll_secs": config.reachability_poll.map(|value| value.as_secs()),
+        "offline_title_suffix": config.offline_title_suffix,
+        "log_max_bytes": config.log_max_bytes,
+        "log_format": if config.log_json { "json" } else { "text" },
+        "pinned_cert_count": config.pinned_cert_sha256.len(),
+        "mtls": config.client_identity.is_some(),
+        "reachability_auth_header": config
+            .reachability_auth_header
+            .as_ref()
+            .map(|(name, _)| format!("{name}: [redacted]")),
+        "webview_headers": config
+            .webview_headers
+            .iter()
+            .map(|(name, _)| format!("{name}: [redacted]"))
+            .collect::<Vec<_>>(),
+        "proxy_url": config.proxy_url.as_ref().map(redacted_url),
+        "minimize_to_tray": config.minimize_to_tray,
+        "kiosk_mode": config.kiosk_mode,
+        "disable_context_menu": config.disable_context_menu,
+        "disable_text_selection": config.disable_text_selection,
+        "enable_devtools": config.enable_devtools,
+        "precheck_allowed_hosts": config.precheck_allowed_hosts,
+        "strict_https": config.strict_https,
+        "strict_allowed_hosts": config.strict_allowed_hosts,
+        "block_data_urls": config.block_data_urls,
+        "disable_gpu": config.disable_gpu,
+        "extra_webview_args": config.extra_webview_args,
+        "allow_https_downgrade": config.allow_https_downgrade,
+        "single_instance": config.single_instance,
+        "audit_navigation": config.audit_navigation,
+        "external_schemes": sorted_entries(&config.external_schemes),
+        "reachable_status_codes": config.reachable_status_codes,
+        "sources": config.value_sources,
+    })
+}
 
-    files.push(PathBuf::from("client.env"));
+/// Restarts the whole process, for changes `reload_config` cannot pick up (the shared
+/// HTTP client, the watchdog task, installed updates). The new process loads the config
+/// from scratch.
+#[tauri::command]
+fn relaunch(app: AppHandle) {
+    append_startup_log_entry(&format!(
+        "relaunch_requested timestamp={}",
+        current_timestamp()
+    ));
+    if let Some(window) = app.get_window("main") {
+        save_window_geometry(&window);
+    }
+    // Otherwise the new process could hand off to this one before it has exited.
+    if let Some(path) = single_instance_port_path() {
+        let _ = fs::remove_file(path);
+    }
+    tauri::api::process::restart(&app.env());
+}
 
-    if let Ok(exe) = std::env::current_exe() {
-        if let Some(dir) = exe.parent() {
-            files.push(dir.join("client.env"));
-        }
+#[tauri::command]
+fn exit_kiosk(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let config = get_config(&state)?;
+    if !config.kiosk_mode {
+        return Err("Kiosk mode is not enabled.".to_string());
     }
+    append_startup_log_entry(&format!("kiosk_exit timestamp={}", current_timestamp()));
+    app.exit(0);
+    Ok(())
+}
 
-    if let Some(path) = appdata_client_env_path() {
-        files.push(path);
+#[tauri::command]
+fn open_devtools(window: Window, state: State<'_, AppState>) -> Result<(), String> {
+    let config = get_config(&state)?;
+    if config.kiosk_mode {
+        return Err("DevTools are blocked in kiosk mode.".to_string());
+    }
+    if !config.enable_devtools && !cfg!(debug_assertions) {
+        return Err("DevTools are disabled. Set ENABLE_DEVTOOLS=true to allow them.".to_string());
     }
+    append_startup_log_entry(&format!(
+        "devtools_opened timestamp={}",
+        current_timestamp()
+    ));
+    window.open_devtools();
+    Ok(())
+}
 
-    files
+/// Tauri 1 exposes no webview capture API, so this fails clearly instead of saving a blank
+/// or partial image. `export_pdf` covers keeping a record of the displayed page.
+#[tauri::command]
+fn capture_screenshot() -> Result<String, String> {
+    append_startup_log_entry("screenshot=unsupported");
+    Err(
+        "Screenshot capture is not supported by this webview runtime. Use Export PDF instead."
+            .to_string(),
+    )
 }
 
-fn appdata_client_env_path() -> Option<PathBuf> {
-    std::env::var("APPDATA").ok().map(|app_data| {
-        PathBuf::from(app_data)
-            .join("CRA Client")
-            .join("client.env")
-    })
+/// Opens the webview print dialog, which offers "Save as PDF" on Windows. Works on the
+/// offline page too, since it prints whatever document is currently shown.
+#[tauri::command]
+fn export_pdf(window: Window) -> Result<(), String> {
+    window
+        .eval("window.print();")
+        .map_err(|error| format!("Failed to open the print dialog: {error}"))?;
+    append_startup_log_entry(&format!(
+        "export_pdf=print_dialog timestamp={} url={}",
+        current_timestamp(),
+        window.url()
+    ));
+    Ok(())
 }
 
-fn appdata_webview_data_path() -> Option<PathBuf> {
-    std::env::var("APPDATA").ok().map(|app_data| {
-        PathBuf::from(app_data)
-            .join("CRA Client")
-            .join("webview")
-    })
+/// Deletes logs (including `bootstrap-result.json`), window state, zoom and last session under
+/// `%APPDATA%\CRA Client`, plus `client.env` / `client.toml` when `include_config` is set.
+/// Items that do not exist are skipped; the rest are attempted even if one fails.
+#[tauri::command]
+fn clear_local_state(
+    state: State<'_, AppState>,
+    include_config: Option<bool>,
+) -> ClearLocalStateResult {
+    let mut targets = vec![
+        appdata_logs_dir_path(),
+        appdata_window_state_path(),
+        appdata_zoom_path(),
+        appdata_last_session_path(),
+    ];
+    if include_config.unwrap_or(false) {
+        let client_env = appdata_client_env_path();
+        targets.push(
+            client_env
+                .as_ref()
+                .map(|path| path.with_file_name("client.toml")),
+        );
+        targets.push(client_env);
+    }
+
+    let mut result = ClearLocalStateResult::default();
+    for path in targets.into_iter().flatten() {
+        let removal = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else if path.is_file() {
+            fs::remove_file(&path)
+        } else {
+            continue;
+        };
+        match removal {
+            Ok(()) => result.removed.push(path.display().to_string()),
+            Err(error) => result.failed.push(format!("{}: {error}", path.display())),
+        }
+    }
+
+    match state.zoom_factor.lock() {
+        Ok(mut guard) => *guard = None,
+        Err(poisoned) => *poisoned.into_inner() = None,
+    }
+    append_startup_log_entry(&format!(
+        "local_state_cleared removed={} failed={}",
+        result.removed.len(),
+        result.failed.len()
+    ));
+    result
 }
 
-fn default_client_env_contents() -> String {
-    format!(
-        "# Auto-generated default configuration for CRA Client.\n\
-# Update APP_URL and ALLOWED_HOSTS if your deployment target changes.\n\
-APP_URL={}\n\
-ALLOWED_HOSTS={}\n\
-WINDOW_TITLE={}\n\
-WINDOW_WIDTH={}\n\
-WINDOW_HEIGHT={}\n\
-# Optional parity gate settings:\n\
-# MIN_WEB_BUILD_HASH=\n\
-# ENFORCE_WEB_BUILD={}\n",
-        DEFAULT_APP_URL,
-        DEFAULT_ALLOWED_HOSTS,
-        DEFAULT_TITLE,
-        DEFAULT_WIDTH as i64,
-        DEFAULT_HEIGHT as i64,
-        if cfg!(debug_assertions) { "false" } else { "true" }
-    )
+#[tauri::command]
+fn open_logs_folder(app: AppHandle) -> Result<(), String> {
+    let path = appdata_logs_dir_path()
+        .ok_or_else(|| "Could not resolve the logs folder: APPDATA is not set.".to_string())?;
+    fs::create_dir_all(&path)
+        .map_err(|error| format!("Could not create logs folder '{}': {error}", path.display()))?;
+
+    tauri::api::shell::open(&app.shell_scope(), path.to_string_lossy(), None)
+        .map_err(|error| format!("Could not open logs folder '{}': {error}", path.display()))
 }
 
-fn ensure_default_client_env_file() -> Result<(), String> {
-    let Some(path) = appdata_client_env_path() else {
-        return Ok(());
-    };
+#[tauri::command]
+fn list_profiles() -> Vec<String> {
+    discover_profiles(&load_client_env_values())
+}
 
-    if path.exists() {
-        return Ok(());
+#[tauri::command]
+async fn switch_profile(
+    window: Window,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    let profiles = discover_profiles(&load_client_env_values());
+    if !profiles
+        .iter()
+        .any(|profile| profile.eq_ignore_ascii_case(&name))
+    {
+        return Err(format!(
+            "Unknown profile '{name}'. Available profiles: {}.",
+            if profiles.is_empty() {
+                "none".to_string()
+            } else {
+                profiles.join(", ")
+            }
+        ));
     }
 
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|error| {
-            format!(
-                "Could not create config directory '{}': {error}",
-                parent.display()
-            )
-        })?;
+    let (config_result, diagnostics) = load_runtime_config_blocking(Some(name.clone())).await;
+    append_startup_log_entry(&format!("----- switch_profile {name} -----"));
+    for entry in &diagnostics {
+        append_startup_log_entry(entry);
     }
-
-    fs::write(&path, default_client_env_contents()).map_err(|error| {
-        format!(
-            "Could not create default config file '{}': {error}",
-            path.display()
-        )
+    let config = config_result.map_err(|error| {
+        append_startup_log_entry(&format!("switch_profile_result=error:{error}"));
+        format!("Profile '{name}' has an invalid configuration: {error}")
     })?;
 
+    let client = build_reachability_client(&config)?;
+    let config = match check_app_targets(&config, &client).await {
+        Ok((selected, _)) => selected,
+        Err(error) => {
+            append_startup_log_entry(&format!("switch_profile_result=unreachable:{error}"));
+            return Err(format!("Profile '{name}' is unreachable: {error}"));
+        }
+    };
+
+    navigate_to_app(&window, &config).await?;
+    append_startup_log_entry("switch_profile_result=ok");
+    state.replace(RuntimeSnapshot {
+        config: Some(config),
+        config_error: None,
+        diagnostics,
+        reachability_client: Some(client),
+    });
+    restart_reachability_watchdog(&window.app_handle());
+
     Ok(())
 }
 
-fn migrate_legacy_default_client_env_file() -> Result<(), String> {
-    let Some(path) = appdata_client_env_path() else {
-        return Ok(());
-    };
+fn runtime_snapshot_from(
+    config_result: Result<RuntimeConfig, ConfigError>,
+    mut diagnostics: Vec<String>,
+    result_key: &str,
+) -> RuntimeSnapshot {
+    let config_result = config_result.and_then(|config| {
+        build_reachability_client(&config)
+            .map(|client| (config, client))
+            .map_err(ConfigError::from)
+    });
+    match config_result {
+        Ok((config, client)) => {
+            apply_log_settings(&config);
+            let entry = format!("{result_key}=ok");
+            append_startup_log_entry(&entry);
+            diagnostics.push(entry);
+            RuntimeSnapshot {
+                config: Some(config),
+                config_error: None,
+                diagnostics,
+                reachability_client: Some(client),
+            }
+        }
+        Err(error) => {
+            let entry = format!("{result_key}=error:{error}");
+            append_startup_log_entry(&entry);
+            diagnostics.push(entry);
+            RuntimeSnapshot {
+                config: None,
+                config_error: Some(error),
+                diagnostics,
+                reachability_client: None,
+            }
+        }
+    }
+}
 
-    if !path.exists() {
-        return Ok(());
+fn get_config(state: &AppState) -> Result<RuntimeConfig, String> {
+    let RuntimeSnapshot {
+        config,
+        config_error,
+        ..
+    } = state.snapshot();
+    config.ok_or_else(|| {
+        config_error.map_or_else(
+            || "Runtime configuration missing.".to_string(),
+            |error| error.to_string(),
+        )
+    })
+}
+
+fn get_config_with_client(
+    state: &AppState,
+) -> Result<(RuntimeConfig, ReachabilityClient), CommandError> {
+    let snapshot = state.snapshot();
+    match (&snapshot.config, &snapshot.reachability_client) {
+        (Some(config), Some(client)) => Ok((config.clone(), client.clone())),
+        _ => Err(CommandError::from_snapshot(&snapshot)),
     }
+}
 
-    let content = match fs::read_to_string(&path) {
-        Ok(value) => value,
-        Err(_) => return Ok(()),
-    };
+fn build_reachability_client(config: &RuntimeConfig) -> Result<ReachabilityClient, String> {
+    reachability_http_builder(config)?
+        .redirect(allowlisted_redirect_policy(config))
+        .build()
+        .map(ReachabilityClient::new)
+        .map_err(|error| format!("HTTP client init failed: {error}"))
+}
 
-    if !content.contains("# Auto-generated default configuration for CRA Client.") {
-        return Ok(());
+/// Timeouts, redirects, proxy and TLS shared by every client the reachability checks use,
+/// without any headers.
+fn reachability_http_builder(config: &RuntimeConfig) -> Result<reqwest::ClientBuilder, String> {
+    http_client_builder(
+        config.reachability_timeout,
+        config.reachability_connect_timeout,
+        config.reachability_max_redirects,
+        config.proxy_url.as_ref(),
+        &config.pinned_cert_sha256,
+        config.client_identity.as_ref(),
+    )
+}
+
+/// The settings behind `reachability_http_builder`, taken one by one so load_runtime_config
+/// can build a client for ALLOWED_HOSTS_URL before the RuntimeConfig exists.
+fn http_client_builder(
+    timeout: Duration,
+    connect_timeout: Duration,
+    max_redirects: usize,
+    proxy_url: Option<&Url>,
+    pinned_cert_sha256: &[[u8; 32]],
+    client_identity: Option<&ClientIdentity>,
+) -> Result<reqwest::ClientBuilder, String> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(timeout)
+        .connect_timeout(connect_timeout)
+        // With redirects disabled the 3xx itself is returned, which still counts as reachable.
+        .redirect(match max_redirects {
+            0 => reqwest::redirect::Policy::none(),
+            limit => reqwest::redirect::Policy::limited(limit),
+        })
+        .user_agent(format!("CRA-Client/{}", env!("CARGO_PKG_VERSION")));
+
+    // Without an explicit proxy reqwest falls back to HTTP(S)_PROXY / NO_PROXY from the
+    // process environment, which load_runtime_config has already validated.
+    if let Some(proxy_url) = proxy_url {
+        let proxy = reqwest::Proxy::all(proxy_url.clone())
+            .map_err(|error| format!("HTTP_PROXY_URL is invalid: {error}"))?
+            .no_proxy(reqwest::NoProxy::from_env());
+        builder = builder.proxy(proxy);
     }
 
-    let legacy = "APP_URL=https://192.168.50.55";
-    if !content.contains(legacy) {
-        return Ok(());
+    if !pinned_cert_sha256.is_empty() || client_identity.is_some() {
+        builder = builder.use_preconfigured_tls(reachability_tls_config(
+            pinned_cert_sha256,
+            client_identity,
+        )?);
     }
 
-    let updated = content.replace(legacy, "APP_URL=http://192.168.50.55:3000");
-    fs::write(&path, updated).map_err(|error| {
-        format!(
-            "Could not migrate legacy config file '{}': {error}",
-            path.display()
-        )
-    })?;
+    Ok(builder)
+}
 
-    Ok(())
+/// A redirect off the allowlist would pass the reachability check but be blocked by
+/// on_navigation later, and would carry WEBVIEW_HEADERS to a third party, so such a hop
+/// fails the request instead of being followed.
+fn allowlisted_redirect_policy(config: &RuntimeConfig) -> reqwest::redirect::Policy {
+    let limit = config.reachability_max_redirects;
+    if limit == 0 {
+        return reqwest::redirect::Policy::none();
+    }
+    let allowed_hosts = config.allowed_hosts.clone();
+    reqwest::redirect::Policy::custom(move |attempt| {
+        let url = attempt.url();
+        let allowed = url.host_str().map(normalize_host).is_some_and(|host| {
+            is_internal_navigation_host(&host)
+                || is_host_allowed(&host, url.port_or_known_default(), &allowed_hosts)
+        });
+        if !allowed {
+            attempt.error(DISALLOWED_REDIRECT)
+        } else if attempt.previous().len() > limit {
+            attempt.error(format!("too many redirects (limit {limit})"))
+        } else {
+            attempt.follow()
+        }
+    })
 }
 
-fn parse_client_env_file(content: &str, output: &mut HashMap<String, String>) {
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            continue;
+/// Adds WEBVIEW_HEADERS to a request for a host in ALLOWED_HOSTS, the same rule the page
+/// script applies to fetch and XHR. Redirects can only reach allowed hosts, see
+/// `allowlisted_redirect_policy`.
+fn with_webview_headers(
+    request: reqwest::RequestBuilder,
+    config: &RuntimeConfig,
+    url: &Url,
+) -> reqwest::RequestBuilder {
+    let allowed = url.host_str().map(normalize_host).is_some_and(|host| {
+        is_host_allowed(&host, url.port_or_known_default(), &config.allowed_hosts)
+    });
+    if !allowed {
+        return request;
+    }
+    config
+        .webview_headers
+        .iter()
+        .fold(request, |request, (name, value)| {
+            request.header(name.clone(), value.clone())
+        })
+}
+
+/// Adds the reachability auth header to a request for the reachability origin. It is
+/// never a client default, so other requests made with the client go without it.
+fn with_reachability_auth(
+    request: reqwest::RequestBuilder,
+    config: &RuntimeConfig,
+    url: &Url,
+) -> reqwest::RequestBuilder {
+    let origin = url.origin();
+    let scoped_out = config
+        .reachability_auth_origin
+        .as_ref()
+        .is_some_and(|auth_origin| *auth_origin != origin);
+    match &config.reachability_auth_header {
+        Some((name, value)) if origin == config.reachability_url.origin() && !scoped_out => {
+            request.header(name.clone(), value.clone())
+        }
+        _ => request,
+    }
+}
+
+async fn check_server_reachable(
+    config: &RuntimeConfig,
+    client: &ReachabilityClient,
+) -> Result<ReachabilityProbe, String> {
+    client
+        .coalesce(&config.reachability_url, async {
+            client.record_maintenance(&config.reachability_url, None);
+            probe_server_reachable(config, client).await
+        })
+        .await
+}
+
+async fn probe_server_reachable(
+    config: &RuntimeConfig,
+    reachability: &ReachabilityClient,
+) -> Result<ReachabilityProbe, String> {
+    let url = &config.reachability_url;
+    let client = &reachability.http;
+
+    let started = Instant::now();
+    let describe_error = |error: reqwest::Error| {
+        if error_chain_contains(&error, CERT_PIN_MISMATCH) {
+            tr(config.locale, "reach.pin_mismatch")
+                .replace("{host}", url.host_str().unwrap_or("server"))
+        } else if error_chain_contains(&error, DISALLOWED_REDIRECT) {
+            let host = error
+                .url()
+                .and_then(Url::host_str)
+                .map_or_else(|| "server".to_string(), normalize_host);
+            tr(config.locale, "reach.disallowed_redirect")
+                .replace("{host}", &host)
+                .replace("{url}", url.as_str())
+        } else {
+            let key = connection_error_key(&error).unwrap_or("reach.unreachable");
+            tr(config.locale, key)
+                .replace("{host}", url.host_str().unwrap_or("server"))
+                .replace(
+                    "{port}",
+                    &url.port_or_known_default()
+                        .map_or_else(String::new, |port| port.to_string()),
+                )
+                .replace("{url}", url.as_str())
+                .replace("{error}", &error.to_string())
+        }
+    };
+    // Only headers are awaited; the body is never read, so a GET costs no more than the
+    // response head on the wire before the connection is dropped.
+    // The JSON contract needs a body, so it always probes with GET.
+    let use_head = config.reachability_head && config.health_expect_json_field.is_none();
+    let mut method = if use_head {
+        reqwest::Method::HEAD
+    } else {
+        reqwest::Method::GET
+    };
+    let send = |method: reqwest::Method| {
+        let request = with_webview_headers(client.request(method, url.clone()), config, url);
+        with_reachability_auth(request, config, url).send()
+    };
+    let mut response = send(method.clone()).await.map_err(describe_error)?;
+    if use_head
+        && matches!(
+            response.status(),
+            reqwest::StatusCode::METHOD_NOT_ALLOWED | reqwest::StatusCode::NOT_IMPLEMENTED
+        )
+    {
+        append_startup_log_entry(&format!(
+            "reachability_method_fallback=GET status={} url={url}",
+            response.status().as_u16()
+        ));
+        method = reqwest::Method::GET;
+        response = send(method.clone()).await.map_err(describe_error)?;
+    }
+
+    // Unlike retry_connect this stays inside one check: a gateway answering 502/503/504
+    // while a new backend starts usually recovers within a second. A 503 with Retry-After
+    // is planned maintenance and is reported straight away.
+    if config.retry_on_gateway_errors {
+        for attempt in 1..=GATEWAY_RETRY_ATTEMPTS {
+            let status = response.status();
+            let transient = matches!(
+                status,
+                reqwest::StatusCode::BAD_GATEWAY
+                    | reqwest::StatusCode::SERVICE_UNAVAILABLE
+                    | reqwest::StatusCode::GATEWAY_TIMEOUT
+            ) && !config.reachable_status_codes.contains(&status.as_u16())
+                && !(status == reqwest::StatusCode::SERVICE_UNAVAILABLE
+                    && response
+                        .headers()
+                        .contains_key(reqwest::header::RETRY_AFTER));
+            if !transient {
+                break;
+            }
+            append_startup_log_entry(&format!(
+                "reachability_gateway_retry attempt={attempt}/{GATEWAY_RETRY_ATTEMPTS} status={} url={url}",
+                status.as_u16()
+            ));
+            tokio::time::sleep(GATEWAY_RETRY_DELAY).await;
+            response = send(method.clone()).await.map_err(describe_error)?;
+        }
+    }
+
+    let status = response.status();
+    if status.is_success()
+        || status.is_redirection()
+        || status.as_u16() == 401
+        || status.as_u16() == 403
+        || config.reachable_status_codes.contains(&status.as_u16())
+    {
+        let content_type_warning = content_type_warning(config, &response);
+        if let Some((field, expected)) = &config.health_expect_json_field {
+            let body = read_body_capped(response, MAX_HEALTH_BODY_BYTES)
+                .await
+                .and_then(|body| {
+                    serde_json::from_slice::<serde_json::Value>(&body)
+                        .map_err(|error| error.to_string())
+                })
+                .map_err(|error| {
+                    tr(config.locale, "reach.health_not_json")
+                        .replace("{url}", url.as_str())
+                        .replace("{error}", &error)
+                })?;
+            let actual = field
+                .split('.')
+                .try_fold(&body, |value, segment| value.get(segment));
+            let matches = match actual {
+                Some(serde_json::Value::String(text)) => text == expected,
+                Some(value) => value.to_string() == *expected,
+                None => false,
+            };
+            if !matches {
+                return Err(tr(config.locale, "reach.health_mismatch")
+                    .replace("{url}", url.as_str())
+                    .replace("{field}", field)
+                    .replace("{expected}", expected)
+                    .replace(
+                        "{actual}",
+                        &actual.map_or_else(|| "missing".to_string(), ToString::to_string),
+                    ));
+            }
+        }
+        return Ok(ReachabilityProbe {
+            latency_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+            status: status.as_u16(),
+            content_type_warning,
+        });
+    }
+
+    if status == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+        if let Some(retry_after) = response.headers().get(reqwest::header::RETRY_AFTER) {
+            let retry_after_secs = retry_after
+                .to_str()
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok());
+            append_startup_log_entry(&format!(
+                "reachability_maintenance url={url} retry_after_secs={}",
+                retry_after_secs.map_or_else(|| "-".to_string(), |secs| secs.to_string())
+            ));
+            reachability.record_maintenance(url, Some(MaintenanceWindow { retry_after_secs }));
+            return Err(tr(config.locale, "reach.maintenance")
+                .replace("{host}", url.host_str().unwrap_or("server")));
+        }
+    }
+
+    Err(tr(config.locale, "reach.status")
+        .replace("{status}", &status.to_string())
+        .replace("{url}", url.as_str()))
+}
+
+/// Flags a 2xx from `APP_URL` itself whose `Content-Type` is not HTML, the usual sign of
+/// `APP_URL` set to an API base. Health-check paths and JSON health contracts are exempt,
+/// as is a response without the header.
+fn content_type_warning(config: &RuntimeConfig, response: &reqwest::Response) -> Option<String> {
+    if !response.status().is_success()
+        || config.reachability_url != config.app_url
+        || config.health_expect_json_field.is_some()
+    {
+        return None;
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)?
+        .to_str()
+        .ok()?;
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    if matches!(mime.as_str(), "text/html" | "application/xhtml+xml") {
+        return None;
+    }
+    append_startup_log_entry(&format!(
+        "reachability_content_type_warning content_type={mime} url={}",
+        config.app_url
+    ));
+    Some(
+        tr(config.locale, "reach.not_html")
+            .replace("{url}", config.app_url.as_str())
+            .replace("{content_type}", &mime),
+    )
+}
+
+/// `Maintenance` when the failed check saw 503 + `Retry-After`, else `Unreachable`.
+fn reachability_command_error(
+    client: &ReachabilityClient,
+    config: &RuntimeConfig,
+    error: String,
+) -> CommandError {
+    if client.maintenance(&config.reachability_url).is_some() {
+        CommandError::Maintenance(error)
+    } else {
+        CommandError::Unreachable(error)
+    }
+}
+
+async fn check_server_reachable_with_backoff(
+    window: &Window,
+    config: &RuntimeConfig,
+    client: &ReachabilityClient,
+) -> Result<(RuntimeConfig, ReachabilityProbe), String> {
+    let mut backoff = Duration::from_millis(RETRY_INITIAL_BACKOFF_MS);
+    let mut last_error = String::new();
+
+    for attempt in 1..=config.retry_attempts {
+        match check_app_targets(config, client).await {
+            Ok((selected, probe)) => {
+                append_startup_log_entry(&format!(
+                    "retry_connect attempt={attempt}/{} result=ok status={} latency_ms={}",
+                    config.retry_attempts, probe.status, probe.latency_ms
+                ));
+                return Ok((selected, probe));
+            }
+            Err(error) => {
+                append_startup_log_entry(&format!(
+                    "retry_connect attempt={attempt}/{} result=error:{error}",
+                    config.retry_attempts
+                ));
+                last_error = error;
+            }
+        }
+
+        let more_attempts = attempt < config.retry_attempts;
+        let progress = RetryProgress {
+            attempt,
+            total: config.retry_attempts,
+            next_delay_ms: more_attempts
+                .then(|| u64::try_from(backoff.as_millis()).unwrap_or(u64::MAX)),
+            error: last_error.clone(),
+        };
+        if let Err(error) = window.emit(RETRY_PROGRESS_EVENT, progress) {
+            append_startup_log_entry(&format!("retry_progress_event=error:{error}"));
+        }
+
+        if more_attempts {
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+
+    Err(last_error)
+}
+
+/// Tries each `APP_URL` target in order and returns a copy of `config` pointed at the
+/// first reachable one. A single-target config is checked as-is.
+async fn check_app_targets(
+    config: &RuntimeConfig,
+    client: &ReachabilityClient,
+) -> Result<(RuntimeConfig, ReachabilityProbe), String> {
+    if config.app_targets.len() <= 1 {
+        return check_server_reachable(config, client)
+            .await
+            .map(|probe| (config.clone(), probe));
+    }
+
+    let mut errors = Vec::new();
+    for target in &config.app_targets {
+        let mut candidate = config.clone();
+        candidate.app_url = target.app_url.clone();
+        candidate.reachability_url = target.reachability_url.clone();
+        match check_server_reachable(&candidate, client).await {
+            Ok(probe) => {
+                if !errors.is_empty() {
+                    append_startup_log_entry(&format!(
+                        "app_url_failover timestamp={} selected={} skipped={}",
+                        current_timestamp(),
+                        redacted_url(&candidate.app_url),
+                        errors.len()
+                    ));
+                }
+                append_startup_log_entry(&format!(
+                    "selected_app_url={}",
+                    redacted_url(&candidate.app_url)
+                ));
+                return Ok((candidate, probe));
+            }
+            Err(error) => {
+                append_startup_log_entry(&format!(
+                    "app_url_candidate_unreachable url={} error={error}",
+                    redacted_url(&candidate.app_url)
+                ));
+                errors.push(error);
+            }
+        }
+    }
+
+    Err(format!(
+        "None of the {} APP_URL targets is reachable: {}",
+        config.app_targets.len(),
+        errors.join("; ")
+    ))
+}
+
+/// Polls once per second until the server answers or `startup_grace` has elapsed, for
+/// servers that boot alongside the client.
+async fn check_server_reachable_with_grace(
+    config: &RuntimeConfig,
+    client: &ReachabilityClient,
+) -> Result<(RuntimeConfig, ReachabilityProbe), String> {
+    let started = Instant::now();
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let result = check_app_targets(config, client).await;
+        if result.is_ok() || started.elapsed() >= config.startup_grace {
+            append_startup_log_entry(&format!(
+                "startup_grace attempts={attempts} result={}",
+                if result.is_ok() { "ok" } else { "unreachable" }
+            ));
+            return result;
+        }
+        tokio::time::sleep(STARTUP_GRACE_POLL_INTERVAL).await;
+    }
+}
+
+// Advisory only: results go to startup.log and never block or fail launch. The probes
+// are unauthenticated, so they use a plain client without WEBVIEW_HEADERS or auth.
+async fn precheck_allowed_hosts(config: RuntimeConfig) {
+    let client = match reachability_http_builder(&config).and_then(|builder| {
+        builder
+            .build()
+            .map_err(|error| format!("HTTP client init failed: {error}"))
+    }) {
+        Ok(client) => client,
+        Err(error) => {
+            append_startup_log_entry(&format!("allowed_host_precheck=skipped error={error}"));
+            return;
+        }
+    };
+    let scheme = config.app_url.scheme().to_string();
+
+    let checks: Vec<_> = sorted_entries(&config.allowed_hosts)
+        .into_iter()
+        .filter(|entry| {
+            let (host, _) = split_host_port(entry);
+            host != "*" && !host.starts_with('.')
+        })
+        .map(|host| {
+            let client = client.clone();
+            let (bare_host, port) = split_host_port(&host);
+            let authority = if bare_host.contains(':') {
+                format!("[{bare_host}]")
+            } else {
+                bare_host.to_string()
+            };
+            let origin = match port {
+                Some(port) => format!("{scheme}://{authority}:{port}/"),
+                None => format!("{scheme}://{authority}/"),
+            };
+            tauri::async_runtime::spawn(async move {
+                let result = client
+                    .get(&origin)
+                    .timeout(ALLOWED_HOST_PRECHECK_TIMEOUT)
+                    .send()
+                    .await;
+                (host, result)
+            })
+        })
+        .collect();
+
+    for check in checks {
+        let Ok((host, result)) = check.await else {
+            continue;
+        };
+        match result {
+            Ok(response) => append_startup_log_entry(&format!(
+                "allowed_host_reachable host={host} ok=true status={}",
+                response.status().as_u16()
+            )),
+            Err(error) => append_startup_log_entry(&format!(
+                "allowed_host_reachable host={host} ok=false error={error}"
+            )),
+        }
+    }
+}
+
+/// Picks a specific message for the common ways a connection fails, so the offline page
+/// says whether the host, the port or the network is at fault.
+fn connection_error_key(error: &reqwest::Error) -> Option<&'static str> {
+    if error.is_timeout() {
+        return Some("reach.timeout");
+    }
+    let mut current: Option<&dyn std::error::Error> = Some(error);
+    while let Some(value) = current {
+        if let Some(io_error) = value.downcast_ref::<std::io::Error>() {
+            match io_error.kind() {
+                std::io::ErrorKind::ConnectionRefused => return Some("reach.refused"),
+                std::io::ErrorKind::ConnectionReset | std::io::ErrorKind::ConnectionAborted => {
+                    return Some("reach.reset")
+                }
+                std::io::ErrorKind::TimedOut => return Some("reach.timeout"),
+                _ => {}
+            }
+        }
+        // hyper reports resolver failures as `dns error: ...` around the OS message.
+        let text = value.to_string().to_ascii_lowercase();
+        if text.contains("dns error")
+            || text.contains("failed to lookup address")
+            || text.contains("no such host")
+        {
+            return Some("reach.dns");
+        }
+        current = value.source();
+    }
+    None
+}
+
+fn error_chain_contains(error: &dyn std::error::Error, needle: &str) -> bool {
+    let mut current: Option<&dyn std::error::Error> = Some(error);
+    while let Some(value) = current {
+        if value.to_string().contains(needle) {
+            return true;
+        }
+        current = value.source();
+    }
+    false
+}
+
+/// Verifies the chain against the bundled web PKI roots, then requires the leaf
+/// certificate's SubjectPublicKeyInfo SHA-256 to be one of the configured pins.
+struct PinnedCertVerifier {
+    inner: WebPkiVerifier,
+    pins: Vec<[u8; 32]>,
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        server_name: &ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            scts,
+            ocsp_response,
+            now,
+        )?;
+
+        let (_, certificate) =
+            x509_parser::parse_x509_certificate(&end_entity.0).map_err(|error| {
+                rustls::Error::General(format!("Invalid leaf certificate: {error}"))
+            })?;
+        let spki_hash: [u8; 32] =
+            Sha256::digest(certificate.tbs_certificate.subject_pki.raw).into();
+
+        if self.pins.contains(&spki_hash) {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(CERT_PIN_MISMATCH.to_string()))
+        }
+    }
+}
+
+/// Client certificate chain and private key for mTLS. `Debug` never prints the key.
+#[derive(Clone)]
+struct ClientIdentity {
+    certs: Vec<Certificate>,
+    key: rustls::PrivateKey,
+}
+
+impl std::fmt::Debug for ClientIdentity {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter
+            .debug_struct("ClientIdentity")
+            .field("certs", &self.certs.len())
+            .field("key", &"[redacted]")
+            .finish()
+    }
+}
+
+/// Splits PEM text into `(label, DER)` blocks, e.g. `("CERTIFICATE", ..)`.
+fn parse_pem_blocks(pem: &str) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(String, String)> = None;
+    for line in pem.lines().map(str::trim) {
+        if let Some(label) = line
+            .strip_prefix("-----BEGIN ")
+            .and_then(|rest| rest.strip_suffix("-----"))
+        {
+            current = Some((label.to_string(), String::new()));
+        } else if let Some(label) = line
+            .strip_prefix("-----END ")
+            .and_then(|rest| rest.strip_suffix("-----"))
+        {
+            let Some((begin_label, body)) = current.take().filter(|(begin, _)| begin == label)
+            else {
+                return Err(format!("unmatched END {label} line"));
+            };
+            let der = base64::engine::general_purpose::STANDARD
+                .decode(body)
+                .map_err(|_| format!("{label} block is not valid base64"))?;
+            blocks.push((begin_label, der));
+        } else if let Some((_, body)) = current.as_mut() {
+            body.push_str(line);
+        }
+    }
+    Ok(blocks)
+}
+
+/// Builds the identity from PEM text holding the certificate chain and one private key
+/// (PKCS#8, PKCS#1 RSA or SEC1 EC, unencrypted).
+fn parse_client_identity(pem: &str) -> Result<ClientIdentity, String> {
+    let mut certs = Vec::new();
+    let mut key = None;
+    for (label, der) in parse_pem_blocks(pem)? {
+        match label.as_str() {
+            "CERTIFICATE" => certs.push(Certificate(der)),
+            "PRIVATE KEY" | "RSA PRIVATE KEY" | "EC PRIVATE KEY" if key.is_none() => {
+                key = Some(rustls::PrivateKey(der));
+            }
+            "ENCRYPTED PRIVATE KEY" => {
+                return Err("encrypted private keys are not supported".to_string())
+            }
+            _ => {}
+        }
+    }
+    if certs.is_empty() {
+        return Err("no CERTIFICATE block found".to_string());
+    }
+    let key = key.ok_or_else(|| "no private key block found".to_string())?;
+    let identity = ClientIdentity { certs, key };
+    // Rejects a key that does not match the certificate before the first check runs.
+    reachability_tls_config(&[], Some(&identity))?;
+    Ok(identity)
+}
+
+/// Builds the identity from a PKCS#12 (`.p12`/`.pfx`) bundle holding one private key and
+/// its certificate chain. An empty password is tried as-is, like Windows exports without one.
+fn parse_pkcs12_identity(der: &[u8], password: &str) -> Result<ClientIdentity, String> {
+    let pfx = p12::PFX::parse(der).map_err(|_| "not a PKCS#12 file".to_string())?;
+    if !pfx.verify_mac(password) {
+        return Err("CLIENT_IDENTITY_PASSWORD is wrong or the file is damaged".to_string());
+    }
+    let undecryptable = |_| "the bundle uses an unsupported encryption scheme".to_string();
+    let mut keys = pfx.key_bags(password).map_err(undecryptable)?;
+    let mut certs: Vec<Certificate> = pfx
+        .cert_x509_bags(password)
+        .map_err(undecryptable)?
+        .into_iter()
+        .map(Certificate)
+        .collect();
+    if keys.len() != 1 {
+        return Err(format!(
+            "expected one private key in the bundle, found {}",
+            keys.len()
+        ));
+    }
+    if certs.is_empty() {
+        return Err("no certificate found in the bundle".to_string());
+    }
+    // rustls sends the chain in order and needs the leaf first; PKCS#12 bags have no
+    // order, so the leaf is the certificate that issued none of the others.
+    let names = certs
+        .iter()
+        .map(|cert| {
+            x509_parser::parse_x509_certificate(&cert.0)
+                .map(|(_, parsed)| {
+                    (
+                        parsed.subject().as_raw().to_vec(),
+                        parsed.issuer().as_raw().to_vec(),
+                    )
+                })
+                .map_err(|_| "a certificate in the bundle is not valid X.509".to_string())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(leaf) = names.iter().position(|(subject, _)| {
+        !names
+            .iter()
+            .any(|(other, issuer)| issuer == subject && other != subject)
+    }) {
+        certs.swap(0, leaf);
+    }
+    let identity = ClientIdentity {
+        certs,
+        key: rustls::PrivateKey(keys.remove(0)),
+    };
+    reachability_tls_config(&[], Some(&identity))?;
+    Ok(identity)
+}
+
+fn reachability_tls_config(
+    pins: &[[u8; 32]],
+    identity: Option<&ClientIdentity>,
+) -> Result<rustls::ClientConfig, String> {
+    let mut roots = RootCertStore::empty();
+    roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|anchor| {
+        OwnedTrustAnchor::from_subject_spki_name_constraints(
+            anchor.subject,
+            anchor.spki,
+            anchor.name_constraints,
+        )
+    }));
+
+    let builder = rustls::ClientConfig::builder().with_safe_defaults();
+    let builder = if pins.is_empty() {
+        builder.with_root_certificates(roots)
+    } else {
+        builder.with_custom_certificate_verifier(Arc::new(PinnedCertVerifier {
+            inner: WebPkiVerifier::new(roots, None),
+            pins: pins.to_vec(),
+        }))
+    };
+    match identity {
+        Some(identity) => builder
+            .with_client_auth_cert(identity.certs.clone(), identity.key.clone())
+            .map_err(|error| format!("client certificate rejected: {error}")),
+        None => Ok(builder.with_no_client_auth()),
+    }
+}
+
+fn parse_header_line(key: &str, raw: &str) -> Result<(HeaderName, HeaderValue), String> {
+    let malformed = || format!("{key} must look like 'Header-Name: value'.");
+    let (name, value) = raw.split_once(':').ok_or_else(malformed)?;
+
+    let name = HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| malformed())?;
+    let mut value = HeaderValue::from_str(value.trim()).map_err(|_| malformed())?;
+    if value.is_empty() {
+        return Err(malformed());
+    }
+    value.set_sensitive(true);
+
+    Ok((name, value))
+}
+
+fn redacted_url(url: &Url) -> String {
+    let mut redacted = url.clone();
+    if !redacted.username().is_empty() || redacted.password().is_some() {
+        let _ = redacted.set_username("");
+        let _ = redacted.set_password(None);
+    }
+    redacted.to_string()
+}
+
+fn basic_auth_header(credentials: &str) -> Result<(HeaderName, HeaderValue), String> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+    let mut value = HeaderValue::from_str(&format!("Basic {encoded}"))
+        .map_err(|_| "APP_URL credentials could not be encoded as a header.".to_string())?;
+    value.set_sensitive(true);
+    Ok((reqwest::header::AUTHORIZATION, value))
+}
+
+fn parse_cert_pin(value: &str) -> Option<[u8; 32]> {
+    let trimmed = value.trim();
+    let trimmed = trimmed.strip_prefix("sha256/").unwrap_or(trimmed);
+
+    let hex: String = trimmed.chars().filter(|c| *c != ':').collect();
+    let bytes = if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        (0..32)
+            .map(|index| u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).ok())
+            .collect::<Option<Vec<u8>>>()?
+    } else {
+        base64::engine::general_purpose::STANDARD
+            .decode(trimmed)
+            .ok()?
+    };
+
+    bytes.try_into().ok()
+}
+
+fn normalized_hash(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+fn deploy_info_url(app_url: &Url) -> Result<Url, String> {
+    let mut deploy_url = app_url.clone();
+    deploy_url
+        .set_path("/api/admin/deploy-info");
+    deploy_url.set_query(None);
+    deploy_url.set_fragment(None);
+    Ok(deploy_url)
+}
+
+// `/healthz` resolves against the APP_URL origin; `healthz` resolves under the APP_URL path,
+// so `https://host/cra/app` (with or without a trailing slash) yields
+// `https://host/cra/app/healthz`. APP_URL query and fragment are never carried over.
+fn health_check_url(app_url: &Url, path: &str) -> Result<Url, String> {
+    let trimmed = path.trim();
+    let mut base = app_url.clone();
+    base.set_query(None);
+    base.set_fragment(None);
+    if !trimmed.starts_with('/') && !base.path().ends_with('/') {
+        let directory = format!("{}/", base.path());
+        base.set_path(&directory);
+    }
+
+    let health_url = base
+        .join(trimmed)
+        .map_err(|error| format!("HEALTH_CHECK_PATH must form a valid URL: {error}"))?;
+
+    if health_url.origin() != app_url.origin() {
+        return Err("HEALTH_CHECK_PATH must stay on the APP_URL origin.".to_string());
+    }
+
+    Ok(health_url)
+}
+
+/// Unlike `HEALTH_CHECK_PATH`, always resolves against the origin, whatever the
+/// `APP_URL` path is.
+fn server_info_url(app_url: &Url, path: &str) -> Result<Url, String> {
+    let url = app_url
+        .join(&format!("/{}", path.trim().trim_start_matches('/')))
+        .map_err(|error| format!("SERVER_INFO_PATH must form a valid URL: {error}"))?;
+    if url.origin() != app_url.origin() {
+        return Err("SERVER_INFO_PATH must stay on the APP_URL origin.".to_string());
+    }
+    Ok(url)
+}
+
+/// Uses the reachability timeouts, proxy and TLS settings, so the parity gate works on the
+/// same networks the reachability check does.
+async fn fetch_deploy_info(config: &RuntimeConfig) -> Result<(String, Option<String>), String> {
+    let deploy_url = deploy_info_url(&config.app_url)?;
+    let client = reachability_http_builder(config)?
+        .build()
+        .map_err(|error| format!("HTTP client init failed: {error}"))?;
+
+    let response = client
+        .get(deploy_url.clone())
+        .send()
+        .await
+        .map_err(|error| format!("Could not fetch deploy info at {deploy_url}: {error}"))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Deploy info endpoint returned status {} at {}",
+            response.status(),
+            deploy_url
+        ));
+    }
+
+    let payload = response
+        .json::<DeployInfoResponse>()
+        .await
+        .map_err(|error| format!("Invalid deploy info response: {error}"))?;
+
+    let hash = payload
+        .build
+        .as_ref()
+        .and_then(|value| value.hash.clone())
+        .or_else(|| payload.git.as_ref().and_then(|value| value.hash.clone()))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| "Deploy info does not include a build or git hash.".to_string())?;
+
+    let build_time = payload
+        .build
+        .as_ref()
+        .and_then(|value| value.built_at.clone())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+
+    Ok((hash, build_time))
+}
+
+#[tauri::command]
+fn get_update_info(state: State<'_, AppState>) -> UpdateInfo {
+    state.update_info()
+}
+
+/// Runs off the UI thread at startup. Failures only produce a diagnostic; the client never
+/// installs anything itself.
+async fn check_for_update(app: AppHandle, config: RuntimeConfig, manifest_url: Url) {
+    match fetch_update_manifest(&config, &manifest_url).await {
+        Ok(manifest) => {
+            let current = env!("CARGO_PKG_VERSION");
+            let available = is_newer_version(&manifest.version, current);
+            append_startup_log_entry(&format!(
+                "update_check latest={} current={current} available={available}",
+                manifest.version
+            ));
+            app.state::<AppState>().set_update_info(UpdateInfo {
+                available,
+                latest_version: Some(manifest.version),
+                download_url: manifest.download_url,
+                notes: manifest.notes,
+            });
+        }
+        Err(error) => append_startup_log_entry(&format!("update_check=error:{error}")),
+    }
+}
+
+/// Uses the reachability timeouts, proxy, pins and client certificate, so a client that can
+/// reach the app behind a proxy can also reach its update manifest.
+async fn fetch_update_manifest(
+    config: &RuntimeConfig,
+    manifest_url: &Url,
+) -> Result<UpdateManifest, String> {
+    let client = reachability_http_builder(config)?
+        .build()
+        .map_err(|error| format!("HTTP client init failed: {error}"))?;
+
+    let response = client
+        .get(manifest_url.clone())
+        .send()
+        .await
+        .map_err(|error| {
+            format!(
+                "Could not fetch update manifest at {}: {error}",
+                redacted_url(manifest_url)
+            )
+        })?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Update manifest returned status {}",
+            response.status()
+        ));
+    }
+
+    response
+        .json::<UpdateManifest>()
+        .await
+        .map_err(|error| format!("Invalid update manifest: {error}"))
+}
+
+/// Compares dotted numeric versions (`1.2.10` > `1.2.9`); a leading `v` and any
+/// `-prerelease`/`+build` suffix are ignored.
+fn is_newer_version(candidate: &str, current: &str) -> bool {
+    fn parts(version: &str) -> Vec<u64> {
+        let version = version.trim().trim_start_matches(['v', 'V']);
+        let core = version.split(['-', '+']).next().unwrap_or_default();
+        core.split('.')
+            .map(|part| part.parse::<u64>().unwrap_or(0))
+            .collect()
+    }
+
+    let (mut candidate, mut current) = (parts(candidate), parts(current));
+    let len = candidate.len().max(current.len());
+    candidate.resize(len, 0);
+    current.resize(len, 0);
+    candidate > current
+}
+
+async fn check_web_build_parity(config: &RuntimeConfig) -> BuildParityResult {
+    let Some(required_raw) = config.min_web_build_hash.clone() else {
+        return BuildParityResult {
+            web_build_hash: None,
+            web_build_time: None,
+            parity_ok: true,
+            parity_error: None,
+        };
+    };
+
+    let required = normalized_hash(&required_raw);
+    if required.is_empty() {
+        return BuildParityResult {
+            web_build_hash: None,
+            web_build_time: None,
+            parity_ok: true,
+            parity_error: None,
+        };
+    }
+
+    match fetch_deploy_info(config).await {
+        Ok((web_hash_raw, web_build_time)) => {
+            let web_hash = normalized_hash(&web_hash_raw);
+            let parity_ok = web_hash.starts_with(&required);
+            let parity_error = if parity_ok {
+                None
+            } else {
+                Some(format!(
+                    "Server build hash '{}' does not match required minimum hash '{}'.",
+                    web_hash_raw, required_raw
+                ))
+            };
+
+            BuildParityResult {
+                web_build_hash: Some(web_hash_raw),
+                web_build_time,
+                parity_ok,
+                parity_error,
+            }
+        }
+        Err(error) => BuildParityResult {
+            web_build_hash: None,
+            web_build_time: None,
+            parity_ok: !config.enforce_web_build,
+            parity_error: Some(format!("Could not verify server build hash: {error}")),
+        },
+    }
+}
+
+fn normalize_host(value: &str) -> String {
+    let lowered = value.trim().to_ascii_lowercase();
+    // IPv6 literals compare in canonical form without brackets or zone ID, since URL hosts
+    // never carry a zone and `[::1]`, `::1` and `0:0:0:0:0:0:0:1` are the same address.
+    let bare = lowered
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .unwrap_or(&lowered);
+    let without_zone = bare.split_once('%').map_or(bare, |(address, _)| address);
+    match without_zone.parse::<std::net::Ipv6Addr>() {
+        Ok(address) => address.to_string(),
+        Err(_) => lowered,
+    }
+}
+
+fn is_release_local_host(host: &str, local_hosts: &HashSet<String>) -> bool {
+    local_hosts.contains(host)
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|address| address.is_loopback())
+}
+
+fn sorted_entries(values: &HashSet<String>) -> Vec<String> {
+    let mut sorted: Vec<String> = values.iter().cloned().collect();
+    sorted.sort();
+    sorted
+}
+
+fn host_matches_pattern(host: &str, pattern: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+
+    if pattern.starts_with('.') {
+        return pattern.len() > 1 && host.len() > pattern.len() && host.ends_with(pattern);
+    }
+
+    host == pattern
+}
+
+/// Splits an `ALLOWED_HOSTS` entry into host and port. Only bracketed IPv6 literals can
+/// carry a port (`[fe80::1]:3000`), since the colons of a bare IPv6 literal are ambiguous.
+fn split_host_port(entry: &str) -> (&str, Option<&str>) {
+    if let Some((host, port)) = entry
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once("]:"))
+    {
+        return (host, Some(port));
+    }
+    match entry.split_once(':') {
+        Some((host, port)) if !port.contains(':') => (host, Some(port)),
+        _ => (entry, None),
+    }
+}
+
+fn normalize_allowed_host_entry(raw: &str) -> Result<String, String> {
+    let (host, port) = split_host_port(raw.trim());
+    let host = normalize_host(host);
+    let Some(port) = port else {
+        return Ok(host);
+    };
+    let port = port
+        .parse::<u16>()
+        .ok()
+        .filter(|port| *port > 0)
+        .ok_or_else(|| format!("ALLOWED_HOSTS entry '{}' has an invalid port.", raw.trim()))?;
+    Ok(if host.contains(':') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    })
+}
+
+/// Entries without a port match any port; `host:port` entries match that port only.
+/// `port` is the effective port of the URL being checked, including scheme defaults.
+fn is_host_allowed(host: &str, port: Option<u16>, allowed_hosts: &HashSet<String>) -> bool {
+    allowed_hosts.contains(host)
+        || allowed_hosts.iter().any(|entry| {
+            let (pattern, entry_port) = split_host_port(entry);
+            let port_matches = match entry_port {
+                Some(entry_port) => entry_port.parse::<u16>().ok() == port,
+                None => true,
+            };
+            port_matches && host_matches_pattern(host, pattern)
+        })
+}
+
+fn current_timestamp() -> String {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs().to_string(),
+        Err(_) => "0".to_string(),
+    }
+}
+
+/// Where the client keeps its files, resolved once per process because logging starts
+/// before the config is loaded.
+struct DataDir {
+    path: Option<PathBuf>,
+    source: String,
+    /// An unusable `DATA_DIR`; the default folder is used for logs so the error is visible.
+    error: Option<String>,
+}
+
+static DATA_DIR: OnceLock<DataDir> = OnceLock::new();
+
+fn data_dir() -> &'static DataDir {
+    DATA_DIR.get_or_init(resolve_data_dir)
+}
+
+/// `CRA_CLIENT_DATA_DIR`, else `DATA_DIR` from the cwd, exe-dir or `--config` files, else
+/// `%APPDATA%\CRA Client`. The AppData client.env cannot move the folder it lives in.
+fn resolve_data_dir() -> DataDir {
+    let default_path = std::env::var("APPDATA")
+        .ok()
+        .map(|app_data| PathBuf::from(app_data).join("CRA Client"));
+
+    let configured = read_process_env_value(ENV_DATA_DIR)
+        .map(|value| (value, format!("process env {ENV_DATA_DIR}")))
+        .or_else(|| {
+            let mut files = local_client_env_files();
+            if let Ok(Some(path)) = cli_config_path() {
+                files.push(path);
+            }
+            let mut found = None;
+            for file in files {
+                let Ok(Some(content)) = read_config_file(&file) else {
+                    continue;
+                };
+                let mut values = HashMap::new();
+                let is_toml = file
+                    .extension()
+                    .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"));
+                if is_toml {
+                    let _ = parse_client_toml_file(&content, &mut values);
+                } else {
+                    parse_client_env_file(&content, &mut values);
+                }
+                if let Some(value) = read_file_value("DATA_DIR", &values) {
+                    found = Some((value, format!("{} DATA_DIR", file.display())));
+                }
+            }
+            found
+        });
+
+    match configured {
+        Some((raw, source)) if Path::new(&raw).is_absolute() => DataDir {
+            path: Some(PathBuf::from(raw)),
+            source,
+            error: None,
+        },
+        Some((raw, source)) => DataDir {
+            path: default_path,
+            source,
+            error: Some(format!("DATA_DIR must be an absolute path, got '{raw}'.")),
+        },
+        None => DataDir {
+            path: default_path,
+            source: "default %APPDATA%\\CRA Client".to_string(),
+            error: None,
+        },
+    }
+}
+
+fn appdata_dir_path() -> Option<PathBuf> {
+    data_dir().path.clone()
+}
+
+fn appdata_logs_dir_path() -> Option<PathBuf> {
+    appdata_dir_path().map(|path| path.join("logs"))
+}
+
+fn startup_log_path() -> Option<PathBuf> {
+    appdata_logs_dir_path().map(|path| path.join("startup.log"))
+}
+
+fn bootstrap_result_path() -> Option<PathBuf> {
+    appdata_logs_dir_path().map(|path| path.join("bootstrap-result.json"))
+}
+
+fn write_bootstrap_result_file(result: &BootstrapState) {
+    let Some(path) = bootstrap_result_path() else {
+        return;
+    };
+    let Ok(serde_json::Value::Object(mut fields)) = serde_json::to_value(result) else {
+        return;
+    };
+    fields.insert(
+        "timestamp".to_string(),
+        serde_json::json!(SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0)),
+    );
+    let Ok(content) = serde_json::to_string_pretty(&fields) else {
+        return;
+    };
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    // Write then rename so a polling monitor never reads a half-written file.
+    let temp_path = path.with_extension("json.tmp");
+    let written = fs::write(&temp_path, content).and_then(|()| fs::rename(&temp_path, &path));
+    if let Err(error) = written {
+        append_startup_log_entry(&format!("bootstrap_result_write=error:{error}"));
+    }
+}
+
+fn rotated_log_path(log_path: &Path, generation: u32) -> PathBuf {
+    let mut name = log_path.as_os_str().to_os_string();
+    name.push(format!(".{generation}"));
+    PathBuf::from(name)
+}
+
+fn rotate_log_if_needed(log_path: &Path) {
+    let max_bytes = LOG_MAX_BYTES.load(Ordering::Relaxed);
+    let Ok(metadata) = fs::metadata(log_path) else {
+        return;
+    };
+    if metadata.len() < max_bytes {
+        return;
+    }
+
+    let _ = fs::remove_file(rotated_log_path(log_path, LOG_ROTATION_GENERATIONS));
+    for generation in (1..LOG_ROTATION_GENERATIONS).rev() {
+        let _ = fs::rename(
+            rotated_log_path(log_path, generation),
+            rotated_log_path(log_path, generation + 1),
+        );
+    }
+    let _ = fs::rename(log_path, rotated_log_path(log_path, 1));
+}
+
+fn append_startup_log_entry(message: &str) {
+    let line = format_log_line(message);
+    let mut pending = match LOG_WRITE_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if write_startup_log_lines(&pending, &line) {
+        pending.lines.clear();
+        pending.dropped = 0;
+        return;
+    }
+    if pending.lines.len() >= LOG_BUFFER_MAX_LINES {
+        pending.lines.pop_front();
+        pending.dropped += 1;
+    }
+    pending.lines.push_back(line);
+}
+
+fn format_log_line(message: &str) -> String {
+    if LOG_JSON.load(Ordering::Relaxed) {
+        json_log_line(message)
+    } else {
+        message.to_string()
+    }
+}
+
+/// Writes any buffered lines followed by `line`; `false` when the log cannot be written.
+fn write_startup_log_lines(pending: &PendingLogLines, line: &str) -> bool {
+    let Some(log_path) = startup_log_path() else {
+        return false;
+    };
+
+    if let Some(parent) = log_path.parent() {
+        if fs::create_dir_all(parent).is_err() {
+            return false;
+        }
+    }
+
+    let mut content = String::new();
+    if pending.dropped > 0 {
+        content.push_str(&format_log_line(&format!(
+            "log_buffer_dropped count={}",
+            pending.dropped
+        )));
+        content.push('\n');
+    }
+    for buffered in pending.lines.iter().map(String::as_str).chain([line]) {
+        content.push_str(buffered);
+        content.push('\n');
+    }
+
+    // Logging stays best-effort: if the lock file cannot be opened or locked, write anyway.
+    let lock_file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(log_path.with_extension("log.lock"))
+        .ok()
+        .filter(|file| file.lock_exclusive().is_ok());
+
+    rotate_log_if_needed(&log_path);
+
+    // One write for all lines so a reader never sees a partial entry.
+    let written = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .is_ok();
+
+    if let Some(lock_file) = lock_file {
+        let _ = FileExt::unlock(&lock_file);
+    }
+    written
+}
+
+// Splits `event=value` / `event key=value ...` entries into a single-line JSON object.
+fn json_log_line(message: &str) -> String {
+    let split_at = message.find(['=', ' ']);
+    let (event, value) = match split_at {
+        Some(index) if index > 0 && !message.starts_with('-') => {
+            (&message[..index], &message[index + 1..])
+        }
+        _ => ("message", message),
+    };
+
+    serde_json::json!({
+        "ts": SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0),
+        "event": event,
+        "value": value,
+    })
+    .to_string()
+}
+
+fn apply_log_settings(config: &RuntimeConfig) {
+    LOG_MAX_BYTES.store(config.log_max_bytes, Ordering::Relaxed);
+    LOG_JSON.store(config.log_json, Ordering::Relaxed);
+}
+
+/// WebView2 reads extra Chromium flags from this variable when its environment is
+/// created, which happens inside `tauri::Builder::run`.
+const WEBVIEW2_BROWSER_ARGS_VAR: &str = "WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS";
+
+/// Flags `EXTRA_WEBVIEW_ARGS` may not set: they would expose the webview to other
+/// processes or move it off the client's data folder.
+const BLOCKED_WEBVIEW_ARGS: &[&str] = &[
+    "--remote-debugging-port",
+    "--remote-debugging-pipe",
+    "--remote-debugging-address",
+    "--user-data-dir",
+];
+
+/// Splits `EXTRA_WEBVIEW_ARGS` on whitespace. Every token must be a `--name` or
+/// `--name=value` flag with a lowercase name. Quotes and control characters are rejected,
+/// so a value cannot smuggle in extra variables or reshape the command line.
+fn parse_extra_webview_args(raw: &str) -> Result<Vec<String>, String> {
+    raw.split_whitespace()
+        .map(|arg| {
+            let name = arg.split_once('=').map_or(arg, |(name, _)| name);
+            let valid_name = name.strip_prefix("--").is_some_and(|rest| {
+                !rest.is_empty()
+                    && rest
+                        .chars()
+                        .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-')
+            });
+            if !valid_name || arg.chars().any(|ch| ch.is_control() || "\"'`".contains(ch)) {
+                return Err(format!(
+                    "EXTRA_WEBVIEW_ARGS entry {arg} is not a --flag or --flag=value."
+                ));
+            }
+            if BLOCKED_WEBVIEW_ARGS.contains(&name) {
+                return Err(format!("EXTRA_WEBVIEW_ARGS may not set {name}."));
+            }
+            Ok(arg.to_string())
+        })
+        .collect()
+}
+
+/// Must run before the builder creates any webview. Flags already in the variable are
+/// kept, and a relaunched child that inherits them does not get duplicates.
+fn apply_webview_environment(config: &RuntimeConfig) {
+    let mut args: Vec<String> = read_process_env_value(WEBVIEW2_BROWSER_ARGS_VAR)
+        .map(|value| value.split_whitespace().map(ToString::to_string).collect())
+        .unwrap_or_default();
+    let existing = args.len();
+    let gpu_arg = config.disable_gpu.then(|| "--disable-gpu".to_string());
+    for arg in gpu_arg.iter().chain(&config.extra_webview_args) {
+        if !args.contains(arg) {
+            args.push(arg.clone());
+        }
+    }
+    if config.disable_gpu {
+        // WebKitGTK has no flag list; this is its switch for accelerated compositing.
+        std::env::set_var("WEBKIT_DISABLE_COMPOSITING_MODE", "1");
+    }
+    if args.len() > existing {
+        std::env::set_var(WEBVIEW2_BROWSER_ARGS_VAR, args.join(" "));
+    }
+    append_startup_log_entry(&format!(
+        "gpu_acceleration={}",
+        if config.disable_gpu {
+            "disabled"
+        } else {
+            "enabled"
+        }
+    ));
+    append_startup_log_entry(&format!(
+        "webview_browser_args={}",
+        if args.is_empty() {
+            "-".to_string()
+        } else {
+            args.join(" ")
+        }
+    ));
+}
+
+fn read_process_env_value(key: &str) -> Option<String> {
+    std::env::var(key).ok().and_then(|value| {
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(trimmed.to_string())
+        }
+    })
+}
+
+fn read_file_value(key: &str, file_values: &HashMap<String, String>) -> Option<String> {
+    file_values
+        .get(key)
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn read_optional_value(
+    file_key: &str,
+    env_key: Option<&str>,
+    file_values: &HashMap<String, String>,
+) -> Option<(String, String)> {
+    if let Some(key) = env_key {
+        if let Some(value) = read_process_env_value(key) {
+            return Some((value, format!("process env {key}")));
+        }
+    }
+
+    read_file_value(file_key, file_values).map(|value| (value, format!("client.env {file_key}")))
+}
+
+fn read_required_value(
+    file_key: &str,
+    env_key: Option<&str>,
+    file_values: &HashMap<String, String>,
+    locale: Locale,
+) -> Result<(String, String), String> {
+    read_optional_value(file_key, env_key, file_values).ok_or_else(|| {
+        tr(locale, "config.missing_required")
+            .replace("{key}", file_key)
+            .replace("{env}", env_key.unwrap_or("environment variable"))
+    })
+}
+
+fn system_locale() -> Locale {
+    sys_locale::get_locale()
+        .as_deref()
+        .and_then(Locale::parse)
+        .unwrap_or(Locale::En)
+}
+
+fn parse_bool_value(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "y" | "on" => Some(true),
+        "0" | "false" | "no" | "n" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn read_bool_value(
+    file_key: &str,
+    env_key: Option<&str>,
+    fallback: bool,
+    file_values: &HashMap<String, String>,
+) -> Result<(bool, String), String> {
+    let Some((raw, source)) = read_optional_value(file_key, env_key, file_values) else {
+        return Ok((fallback, format!("default {fallback}")));
+    };
+
+    let Some(value) = parse_bool_value(&raw) else {
+        return Err(format!(
+            "{file_key} must be a boolean (true/false/1/0), got '{raw}'."
+        ));
+    };
+
+    Ok((value, source))
+}
+
+/// How `WINDOW_WIDTH` / `WINDOW_HEIGHT` pixel values are read. `Physical` values are
+/// divided by the scale factor of the monitor the window opens on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WindowSizeUnit {
+    Logical,
+    Physical,
+}
+
+impl WindowSizeUnit {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Logical => "logical",
+            Self::Physical => "physical",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum WindowDimension {
+    Pixels(f64),
+    /// Share of the monitor size, in `(0, 100]`.
+    Percent(f64),
+}
+
+fn parse_window_dimension(
+    file_key: &str,
+    env_key: Option<&str>,
+    fallback: f64,
+    file_values: &HashMap<String, String>,
+) -> Result<(WindowDimension, String), String> {
+    let Some((raw, source)) = read_optional_value(file_key, env_key, file_values) else {
+        return Ok((
+            WindowDimension::Pixels(fallback),
+            format!("default {fallback}"),
+        ));
+    };
+
+    if let Some(percent) = raw.strip_suffix('%') {
+        return percent
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite() && *value > 0.0 && *value <= 100.0)
+            .map(|value| (WindowDimension::Percent(value), source))
+            .ok_or_else(|| {
+                format!("{file_key} percentage must be between 0 and 100%, got '{raw}'.")
+            });
+    }
+
+    raw.parse::<f64>()
+        .map(|value| (WindowDimension::Pixels(value), source))
+        .map_err(|_| format!("{file_key} must be numeric or a percentage, got '{raw}'."))
+}
+
+/// Initial inner size in logical pixels for a window on `monitor`, before min/max clamping.
+fn initial_window_size(config: &RuntimeConfig, monitor: &tauri::Monitor) -> LogicalSize<f64> {
+    let scale_factor = monitor.scale_factor();
+    let monitor_size = monitor.size().to_logical::<f64>(scale_factor);
+    let resolve = |pixels: f64, percent: Option<f64>, monitor_extent: f64| match percent {
+        Some(percent) => monitor_extent * percent / 100.0,
+        None if config.window_size_unit == WindowSizeUnit::Physical => pixels / scale_factor,
+        None => pixels,
+    };
+    LogicalSize::new(
+        resolve(
+            config.window_width,
+            config.window_width_percent,
+            monitor_size.width,
+        ),
+        resolve(
+            config.window_height,
+            config.window_height_percent,
+            monitor_size.height,
+        ),
+    )
+}
+
+fn parse_optional_window_dimension(
+    file_key: &str,
+    env_key: Option<&str>,
+    file_values: &HashMap<String, String>,
+) -> Result<Option<(f64, String)>, String> {
+    let Some((raw, source)) = read_optional_value(file_key, env_key, file_values) else {
+        return Ok(None);
+    };
+
+    raw.parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite() && *value > 0.0)
+        .map(|value| Some((value, source)))
+        .ok_or_else(|| format!("{file_key} must be a positive number, got '{raw}'."))
+}
+
+/// Accepts `#RGB`, `#RRGGBB` or `#RRGGBBAA` and returns it lowercased. Anything else is
+/// rejected so the value can be inlined into CSS and scripts without escaping.
+fn parse_hex_color(raw: &str) -> Option<String> {
+    let digits = raw.strip_prefix('#')?;
+    let valid =
+        matches!(digits.len(), 3 | 6 | 8) && digits.chars().all(|value| value.is_ascii_hexdigit());
+    valid.then(|| format!("#{}", digits.to_ascii_lowercase()))
+}
+
+fn validate_window_bounds(
+    axis: &str,
+    size: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+) -> Result<(), String> {
+    if let Some(min) = min.filter(|min| *min > size) {
+        return Err(format!(
+            "WINDOW_MIN_{axis} ({min}) must not exceed WINDOW_{axis} ({size})."
+        ));
+    }
+    if let Some(max) = max.filter(|max| *max < size) {
+        return Err(format!(
+            "WINDOW_MAX_{axis} ({max}) must not be smaller than WINDOW_{axis} ({size})."
+        ));
+    }
+    Ok(())
+}
+
+fn parse_timeout_secs(
+    file_key: &str,
+    env_key: Option<&str>,
+    fallback: u64,
+    file_values: &HashMap<String, String>,
+) -> Result<(u64, String), String> {
+    let Some((raw, source)) = read_optional_value(file_key, env_key, file_values) else {
+        return Ok((fallback, format!("default {fallback}")));
+    };
+
+    let value = raw.parse::<u64>().map_err(|_| {
+        format!("{file_key} must be a positive whole number of seconds, got '{raw}'.")
+    })?;
+    if value == 0 {
+        return Err(format!(
+            "{file_key} must be greater than zero, got '{raw}'."
+        ));
+    }
+
+    let clamped = value.clamp(MIN_REACHABILITY_TIMEOUT_SECS, MAX_REACHABILITY_TIMEOUT_SECS);
+    if clamped != value {
+        return Ok((clamped, format!("{source} (clamped from {value})")));
+    }
+
+    Ok((value, source))
+}
+
+fn parse_positive_integer(
+    file_key: &str,
+    env_key: Option<&str>,
+    fallback: u64,
+    file_values: &HashMap<String, String>,
+) -> Result<(u64, String), String> {
+    let Some((raw, source)) = read_optional_value(file_key, env_key, file_values) else {
+        return Ok((fallback, format!("default {fallback}")));
+    };
+
+    match raw.parse::<u64>() {
+        Ok(value) if value > 0 => Ok((value, source)),
+        _ => Err(format!(
+            "{file_key} must be a positive whole number, got '{raw}'."
+        )),
+    }
+}
+
+/// The cwd and exe-dir config files, lowest precedence first.
+fn local_client_env_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    files.push(PathBuf::from("client.env"));
+    files.push(PathBuf::from("client.toml"));
+
+    if let Ok(exe) = std::env::current_exe() {
+        if let Some(dir) = exe.parent() {
+            files.push(dir.join("client.env"));
+            files.push(dir.join("client.toml"));
+        }
+    }
+
+    files
+}
+
+/// Each `client.env` location may also hold a `client.toml`, read right after it so its
+/// keys win within that location.
+fn candidate_client_env_files() -> Vec<PathBuf> {
+    let mut files = local_client_env_files();
+
+    if let Some(path) = appdata_client_env_path() {
+        let toml_path = path.with_file_name("client.toml");
+        files.push(path);
+        files.push(toml_path);
+    }
+
+    // Later files override earlier ones, so the `--config` file goes last to win.
+    if let Ok(Some(path)) = cli_config_path() {
+        files.push(path);
+    }
+
+    files
+}
+
+/// Reads `--config <path>` (or `--config=<path>`) from the command line.
+fn cli_config_path() -> Result<Option<PathBuf>, String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return match args.next() {
+                Some(path) if !path.trim().is_empty() => Ok(Some(PathBuf::from(path))),
+                _ => Err("--config requires a path to a client.env file.".to_string()),
+            };
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            if path.trim().is_empty() {
+                return Err("--config requires a path to a client.env file.".to_string());
+            }
+            return Ok(Some(PathBuf::from(path)));
+        }
+    }
+    Ok(None)
+}
+
+/// Headless modes for provisioning scripts; all exit before a window or tray exists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CliMode {
+    /// `--print-config`: print the effective (redacted) config, or the config error.
+    PrintConfig,
+    /// `--check`: print only whether the config loads.
+    Check,
+    /// `--self-test`: also probe the server, decode the icons and write to the log folder.
+    SelfTest,
+}
+
+fn cli_mode() -> Option<CliMode> {
+    std::env::args().skip(1).find_map(|arg| match arg.as_str() {
+        "--print-config" => Some(CliMode::PrintConfig),
+        "--check" => Some(CliMode::Check),
+        "--self-test" => Some(CliMode::SelfTest),
+        _ => None,
+    })
+}
+
+/// Prints one JSON document to stdout and returns the process exit code: 0 when the
+/// config loads (including building the reachability client), 1 otherwise.
+fn run_cli_mode(mode: CliMode) -> i32 {
+    attach_parent_console();
+    if mode == CliMode::SelfTest {
+        return run_self_test();
+    }
+    let (config_result, diagnostics) = load_runtime_config(None);
+    let config_result = config_result
+        .map_err(|error| error.to_string())
+        .and_then(|config| build_reachability_client(&config).map(|_| config));
+    let output = match (&config_result, mode) {
+        (Ok(config), CliMode::PrintConfig) => serde_json::json!({
+            "ok": true,
+            "version": env!("CARGO_PKG_VERSION"),
+            "config": effective_config_json(config),
+            "diagnostics": diagnostics,
+        }),
+        (Ok(_), _) => serde_json::json!({ "ok": true }),
+        (Err(error), _) => serde_json::json!({
+            "ok": false,
+            "error": error,
+            "diagnostics": diagnostics,
+        }),
+    };
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&output).unwrap_or_else(|_| output.to_string())
+    );
+    i32::from(config_result.is_err())
+}
+
+/// Release builds use the GUI subsystem, so a console launch leaves stdout unset and the
+/// CLI report would vanish. Joining the parent console fixes that; a redirected stdout is
+/// already set and left alone.
+#[cfg(windows)]
+fn attach_parent_console() {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+        fn GetStdHandle(std_handle: u32) -> *mut std::ffi::c_void;
+    }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    const STD_OUTPUT_HANDLE: u32 = -11_i32 as u32;
+    // SAFETY: both calls only take plain integers and have no memory preconditions. A
+    // failed attach (no parent console) just leaves stdout unset, as before.
+    unsafe {
+        if GetStdHandle(STD_OUTPUT_HANDLE).is_null() {
+            AttachConsole(ATTACH_PARENT_PROCESS);
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
+
+/// Packaging check for post-install steps: every check runs (none stop the others) and the
+/// exit code is 1 when any fails. Prints `{ok, version, checks: [{name, passed, message}]}`.
+/// Network checks use the configured reachability timeouts.
+fn run_self_test() -> i32 {
+    let mut checks = Vec::new();
+    let (config_result, _) = load_runtime_config(None);
+    let loaded = record_app_url_check(
+        &mut checks,
+        "config",
+        config_result
+            .map_err(|error| error.to_string())
+            .and_then(|config| build_reachability_client(&config).map(|client| (config, client))),
+    );
+
+    let reachability = match &loaded {
+        Some((config, client)) => {
+            tauri::async_runtime::block_on(check_app_targets(config, client)).map(|_| ())
+        }
+        None => Err("Skipped: the config did not load.".to_string()),
+    };
+    record_app_url_check(&mut checks, "reachability", reachability);
+
+    record_app_url_check(
+        &mut checks,
+        "embedded_icon",
+        check_ico_bytes(include_bytes!("../icons/icon.ico")),
+    );
+    if let Some(path) = loaded
+        .as_ref()
+        .and_then(|(config, _)| config.window_icon_path.as_ref())
+    {
+        let result = fs::read(path)
+            .map_err(|error| format!("Could not read '{}': {error}", path.display()))
+            .and_then(|bytes| check_png_bytes(&bytes));
+        record_app_url_check(&mut checks, "window_icon", result);
+    }
+
+    record_app_url_check(&mut checks, "log_dir", check_log_dir_writable());
+
+    let ok = checks.iter().all(|check| check.passed);
+    let output = serde_json::json!({
+        "ok": ok,
+        "version": env!("CARGO_PKG_VERSION"),
+        "checks": checks,
+    });
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&output).unwrap_or_else(|_| output.to_string())
+    );
+    i32::from(!ok)
+}
+
+/// Walks the ICO directory: every image entry must lie inside the file.
+fn check_ico_bytes(bytes: &[u8]) -> Result<(), String> {
+    let read_u16 = |at: usize| {
+        bytes
+            .get(at..at + 2)
+            .map(|raw| u16::from_le_bytes([raw[0], raw[1]]))
+    };
+    let read_u32 = |at: usize| {
+        bytes
+            .get(at..at + 4)
+            .map(|raw| u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as usize)
+    };
+    if read_u16(0) != Some(0) || read_u16(2) != Some(1) {
+        return Err("Icon is not an ICO file.".to_string());
+    }
+    let count = usize::from(read_u16(4).unwrap_or(0));
+    if count == 0 {
+        return Err("Icon contains no images.".to_string());
+    }
+    for index in 0..count {
+        let entry = 6 + index * 16;
+        let (Some(size), Some(offset)) = (read_u32(entry + 8), read_u32(entry + 12)) else {
+            return Err(format!("Icon directory entry {index} is truncated."));
+        };
+        let inside = offset
+            .checked_add(size)
+            .is_some_and(|end| end <= bytes.len());
+        if size == 0 || !inside {
+            return Err(format!("Icon image {index} lies outside the file."));
+        }
+    }
+    Ok(())
+}
+
+fn check_png_bytes(bytes: &[u8]) -> Result<(), String> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if !bytes.starts_with(SIGNATURE) || bytes.get(12..16) != Some(b"IHDR".as_slice()) {
+        return Err("Icon is not a PNG file.".to_string());
+    }
+    Ok(())
+}
+
+fn check_log_dir_writable() -> Result<(), String> {
+    let dir = appdata_logs_dir_path()
+        .ok_or_else(|| "Could not resolve the log folder: APPDATA is not set.".to_string())?;
+    fs::create_dir_all(&dir)
+        .map_err(|error| format!("Could not create '{}': {error}", dir.display()))?;
+    let probe = dir.join("self-test.tmp");
+    fs::write(&probe, b"self-test")
+        .map_err(|error| format!("Could not write to '{}': {error}", dir.display()))?;
+    let _ = fs::remove_file(probe);
+    Ok(())
+}
+
+fn appdata_client_env_path() -> Option<PathBuf> {
+    appdata_dir_path().map(|path| path.join("client.env"))
+}
+
+fn appdata_window_state_path() -> Option<PathBuf> {
+    appdata_logs_dir_path()
+        .and_then(|path| path.parent().map(|parent| parent.join("window-state.json")))
+}
+
+fn appdata_https_hosts_path() -> Option<PathBuf> {
+    appdata_logs_dir_path()
+        .and_then(|path| path.parent().map(|parent| parent.join("https-hosts.json")))
+}
+
+fn appdata_allowed_hosts_cache_path() -> Option<PathBuf> {
+    appdata_logs_dir_path().and_then(|path| {
+        path.parent()
+            .map(|parent| parent.join("allowed-hosts-cache.json"))
+    })
+}
+
+/// Last successful `ALLOWED_HOSTS_URL` download, used when the next fetch fails.
+#[derive(Serialize, Deserialize)]
+struct AllowedHostsCache {
+    url: String,
+    hosts: Vec<String>,
+    fetched_at: String,
+}
+
+fn load_allowed_hosts_cache(url: &Url) -> Option<AllowedHostsCache> {
+    let content = fs::read_to_string(appdata_allowed_hosts_cache_path()?).ok()?;
+    serde_json::from_str::<AllowedHostsCache>(&content)
+        .ok()
+        .filter(|cache| cache.url == redacted_url(url))
+}
+
+fn save_allowed_hosts_cache(url: &Url, hosts: &HashSet<String>) {
+    let Some(path) = appdata_allowed_hosts_cache_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        if fs::create_dir_all(parent).is_err() {
+            return;
+        }
+    }
+    let cache = AllowedHostsCache {
+        url: redacted_url(url),
+        hosts: sorted_entries(hosts),
+        fetched_at: current_timestamp(),
+    };
+    if let Ok(content) = serde_json::to_string(&cache) {
+        let _ = fs::write(path, content);
+    }
+}
+
+/// Config loading is synchronous, so the download gets its own thread to block on; the
+/// async commands run the whole load through `load_runtime_config_blocking`.
+fn fetch_remote_allowed_hosts(
+    url: &Url,
+    builder: reqwest::ClientBuilder,
+) -> Result<HashSet<String>, String> {
+    let url = url.clone();
+    std::thread::spawn(move || {
+        tauri::async_runtime::block_on(download_remote_allowed_hosts(&url, builder))
+    })
+    .join()
+    .map_err(|_| "allowed hosts download thread panicked".to_string())?
+}
+
+/// Reads at most `limit` bytes, chunk by chunk, so an oversized or endless body fails
+/// instead of being buffered whole.
+async fn read_body_capped(mut response: reqwest::Response, limit: u64) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|error| error.to_string())? {
+        if (body.len() + chunk.len()) as u64 > limit {
+            return Err(format!("larger than {limit} bytes"));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Entries are separated by commas or newlines; blank lines and `#` comments are skipped.
+/// One invalid entry rejects the whole list so a truncated download is never merged.
+async fn download_remote_allowed_hosts(
+    url: &Url,
+    builder: reqwest::ClientBuilder,
+) -> Result<HashSet<String>, String> {
+    let client = builder
+        .https_only(true)
+        .build()
+        .map_err(|error| format!("HTTP client init failed: {error}"))?;
+
+    let response = client.get(url.clone()).send().await.map_err(|error| {
+        format!(
+            "Could not fetch allowed hosts at {}: {error}",
+            redacted_url(url)
+        )
+    })?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Allowed hosts list returned status {}",
+            response.status()
+        ));
+    }
+    let body = read_body_capped(response, MAX_CONFIG_FILE_BYTES)
+        .await
+        .map_err(|error| format!("Could not read allowed hosts list: {error}"))?;
+    let body = String::from_utf8(body)
+        .map_err(|_| "Allowed hosts list is not valid UTF-8.".to_string())?;
+
+    let hosts = body
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .flat_map(|line| line.split(','))
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(normalize_allowed_host_entry)
+        .collect::<Result<HashSet<String>, String>>()?;
+    if hosts.is_empty() {
+        return Err("Allowed hosts list is empty.".to_string());
+    }
+    Ok(hosts)
+}
+
+fn load_https_seen_hosts() -> HashSet<String> {
+    appdata_https_hosts_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<Vec<String>>(&content).ok())
+        .map(|hosts| hosts.iter().map(|host| normalize_host(host)).collect())
+        .unwrap_or_default()
+}
+
+fn save_https_seen_hosts(hosts: &HashSet<String>) {
+    let Some(path) = appdata_https_hosts_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        if fs::create_dir_all(parent).is_err() {
+            return;
+        }
+    }
+    if let Ok(content) = serde_json::to_string(&sorted_entries(hosts)) {
+        let _ = fs::write(path, content);
+    }
+}
+
+fn appdata_last_session_path() -> Option<PathBuf> {
+    appdata_logs_dir_path()
+        .and_then(|path| path.parent().map(|parent| parent.join("last-session.json")))
+}
+
+fn load_last_session_url() -> Option<Url> {
+    let content = fs::read_to_string(appdata_last_session_path()?).ok()?;
+    let value = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+    Url::parse(value["url"].as_str()?).ok()
+}
+
+/// Records a page the main window finished loading. Only remote app pages are kept,
+/// never the bootstrap screen, and credentials are stripped before writing.
+fn save_last_session_url(url: &Url) {
+    let remote = matches!(url.scheme(), "http" | "https")
+        && url
+            .host_str()
+            .is_some_and(|host| !is_internal_navigation_host(&normalize_host(host)));
+    if !remote {
+        return;
+    }
+    let Some(path) = appdata_last_session_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        if fs::create_dir_all(parent).is_err() {
+            return;
+        }
+    }
+    let _ = fs::write(
+        path,
+        serde_json::json!({ "url": redacted_url(url), "saved_at": current_timestamp() })
+            .to_string(),
+    );
+}
+
+/// The saved last-session URL, if its host and port are still in `ALLOWED_HOSTS`.
+fn resumable_session_url(config: &RuntimeConfig) -> Option<Url> {
+    load_last_session_url().filter(|url| {
+        matches!(url.scheme(), "http" | "https")
+            && url.host_str().is_some_and(|host| {
+                is_host_allowed(
+                    &normalize_host(host),
+                    url.port_or_known_default(),
+                    &config.allowed_hosts,
+                )
+            })
+    })
+}
+
+fn appdata_zoom_path() -> Option<PathBuf> {
+    appdata_logs_dir_path().and_then(|path| path.parent().map(|parent| parent.join("zoom.json")))
+}
+
+fn load_zoom_factor() -> Option<f64> {
+    let content = fs::read_to_string(appdata_zoom_path()?).ok()?;
+    let value = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+    value["factor"]
+        .as_f64()
+        .filter(|factor| factor.is_finite())
+        .map(clamp_zoom)
+}
+
+fn save_zoom_factor(factor: f64) {
+    let Some(path) = appdata_zoom_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        if fs::create_dir_all(parent).is_err() {
+            return;
+        }
+    }
+    let _ = fs::write(path, serde_json::json!({ "factor": factor }).to_string());
+}
+
+/// Rounds to whole percent so repeated steps do not drift, then clamps to 50%-300%.
+fn clamp_zoom(factor: f64) -> f64 {
+    ((factor * 100.0).round() / 100.0).clamp(MIN_WINDOW_ZOOM, MAX_WINDOW_ZOOM)
+}
+
+fn load_window_geometry() -> Option<WindowGeometry> {
+    let path = appdata_window_state_path()?;
+    let content = fs::read_to_string(path).ok()?;
+    let geometry = serde_json::from_str::<WindowGeometry>(&content).ok()?;
+
+    if !geometry.width.is_finite() || !geometry.height.is_finite() {
+        return None;
+    }
+
+    Some(geometry)
+}
+
+fn save_window_geometry(window: &Window) {
+    let Some(path) = appdata_window_state_path() else {
+        return;
+    };
+
+    if window.is_minimized().unwrap_or(false) || window.is_maximized().unwrap_or(false) {
+        return;
+    }
+
+    let (Ok(position), Ok(size), Ok(scale_factor)) = (
+        window.outer_position(),
+        window.inner_size(),
+        window.scale_factor(),
+    ) else {
+        return;
+    };
+    let logical_size = size.to_logical::<f64>(scale_factor);
+
+    let geometry = WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: logical_size.width,
+        height: logical_size.height,
+    };
+
+    if let Ok(content) = serde_json::to_string(&geometry) {
+        if let Some(parent) = path.parent() {
+            if fs::create_dir_all(parent).is_err() {
+                return;
+            }
+        }
+        let _ = fs::write(path, content);
+    }
+}
+
+fn restore_window_position(window: &Window, geometry: &WindowGeometry) {
+    let Ok(monitors) = window.available_monitors() else {
+        return;
+    };
+
+    let visible_on = monitors.iter().find(|monitor| {
+        let origin = monitor.position();
+        let size = monitor.size();
+        let right = origin.x + size.width as i32;
+        let bottom = origin.y + size.height as i32;
+        geometry.x + MIN_VISIBLE_WINDOW_EDGE > origin.x
+            && geometry.x < right - MIN_VISIBLE_WINDOW_EDGE
+            && geometry.y >= origin.y
+            && geometry.y < bottom - MIN_VISIBLE_WINDOW_EDGE
+    });
+
+    let position = match visible_on {
+        Some(_) => PhysicalPosition::new(geometry.x, geometry.y),
+        None => {
+            // Saved on a monitor that is no longer attached; pull it onto the primary one.
+            let Ok(Some(monitor)) = window.primary_monitor() else {
+                return;
+            };
+            let origin = monitor.position();
+            let size = monitor.size();
+            let outer_width = window.outer_size().map(|value| value.width).unwrap_or(0);
+            let outer_height = window.outer_size().map(|value| value.height).unwrap_or(0);
+            let max_x = origin.x + (size.width.saturating_sub(outer_width)) as i32;
+            let max_y = origin.y + (size.height.saturating_sub(outer_height)) as i32;
+            PhysicalPosition::new(
+                geometry.x.clamp(origin.x, max_x),
+                geometry.y.clamp(origin.y, max_y),
+            )
+        }
+    };
+
+    let _ = window.set_position(position);
+}
+
+fn appdata_webview_data_path() -> Option<PathBuf> {
+    appdata_dir_path().map(|path| path.join("webview"))
+}
+
+fn default_client_env_contents() -> String {
+    format!(
+        "# Auto-generated default configuration for CRA Client.\n\
+# Update APP_URL and ALLOWED_HOSTS if your deployment target changes.\n\
+APP_URL={}\n\
+ALLOWED_HOSTS={}\n\
+WINDOW_TITLE={}\n\
+WINDOW_WIDTH={}\n\
+WINDOW_HEIGHT={}\n\
+# Optional parity gate settings:\n\
+# MIN_WEB_BUILD_HASH=\n\
+# ENFORCE_WEB_BUILD={}\n",
+        DEFAULT_APP_URL,
+        DEFAULT_ALLOWED_HOSTS,
+        DEFAULT_TITLE,
+        DEFAULT_WIDTH as i64,
+        DEFAULT_HEIGHT as i64,
+        if cfg!(debug_assertions) { "false" } else { "true" }
+    )
+}
+
+fn ensure_default_client_env_file() -> Result<(), String> {
+    let Some(path) = appdata_client_env_path() else {
+        return Ok(());
+    };
+
+    if path.exists() {
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| {
+            format!(
+                "Could not create config directory '{}': {error}",
+                parent.display()
+            )
+        })?;
+    }
+
+    fs::write(&path, default_client_env_contents()).map_err(|error| {
+        format!(
+            "Could not create default config file '{}': {error}",
+            path.display()
+        )
+    })?;
+
+    Ok(())
+}
+
+fn migrate_legacy_default_client_env_file() -> Result<(), String> {
+    let Some(path) = appdata_client_env_path() else {
+        return Ok(());
+    };
+
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let Ok(Some(content)) = read_config_file(&path) else {
+        return Ok(());
+    };
+
+    if !content.contains("# Auto-generated default configuration for CRA Client.") {
+        return Ok(());
+    }
+
+    let legacy = "APP_URL=https://192.168.50.55";
+    if !content.contains(legacy) {
+        return Ok(());
+    }
+
+    let updated = content.replace(legacy, "APP_URL=http://192.168.50.55:3000");
+    fs::write(&path, updated).map_err(|error| {
+        format!(
+            "Could not migrate legacy config file '{}': {error}",
+            path.display()
+        )
+    })?;
+
+    Ok(())
+}
+
+/// Returns a diagnostic for each `${VAR}` reference that could not be resolved.
+fn parse_client_env_file(content: &str, output: &mut HashMap<String, String>) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some((key, value)) = trimmed.split_once('=') {
+            // Accept shell-style `export KEY=value` lines.
+            let key_trimmed = key.trim();
+            let key_trimmed = key_trimmed
+                .strip_prefix("export ")
+                .map(str::trim)
+                .unwrap_or(key_trimmed);
+            if key_trimmed.is_empty() {
+                continue;
+            }
+
+            // Single-quoted values are taken literally, as in a shell.
+            let value = value.trim();
+            let cleaned = clean_env_value(value);
+            let cleaned = if value.starts_with('\'') {
+                cleaned
+            } else {
+                let (expanded, unresolved) = expand_env_references(&cleaned);
+                for name in unresolved {
+                    warnings.push(format!(
+                        "env_expansion_unresolved key={key_trimmed} var={name} (left as written)"
+                    ));
+                }
+                expanded
+            };
+            output.insert(key_trimmed.to_string(), cleaned);
+        }
+    }
+    warnings
+}
+
+/// Expands `${VAR}` from the process environment and turns `$${` into a literal `${`.
+/// Any other `$` is kept as written so values like passwords are not mangled.
+fn expand_env_references(value: &str) -> (String, Vec<String>) {
+    let is_name = |name: &str| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    let mut expanded = String::with_capacity(value.len());
+    let mut unresolved = Vec::new();
+    let mut rest = value;
+    while let Some(index) = rest.find('$') {
+        expanded.push_str(&rest[..index]);
+        let tail = &rest[index..];
+        if let Some(after) = tail.strip_prefix("$${") {
+            expanded.push_str("${");
+            rest = after;
+            continue;
+        }
+        match tail
+            .strip_prefix("${")
+            .and_then(|inner| inner.split_once('}'))
+            .filter(|(name, _)| is_name(name))
+        {
+            Some((name, after)) => {
+                match std::env::var(name) {
+                    Ok(resolved) => expanded.push_str(&resolved),
+                    Err(_) => {
+                        expanded.push_str(&tail[..name.len() + 3]);
+                        unresolved.push(name.to_string());
+                    }
+                }
+                rest = after;
+            }
+            None => {
+                expanded.push('$');
+                rest = &tail[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    (expanded, unresolved)
+}
+
+fn clean_env_value(value: &str) -> String {
+    for quote in ['"', '\''] {
+        let Some(rest) = value.strip_prefix(quote) else {
+            continue;
+        };
+        if let Some((inner, trailing)) = rest.split_once(quote) {
+            let trailing = trailing.trim_start();
+            if trailing.is_empty() || trailing.starts_with('#') {
+                return inner.to_string();
+            }
+        }
+    }
+
+    // An unquoted `#` only starts a comment after whitespace, so URL fragments survive.
+    let comment_start = value
+        .char_indices()
+        .find(|(index, c)| *c == '#' && value[..*index].ends_with(char::is_whitespace))
+        .map(|(index, _)| index);
+    match comment_start {
+        Some(index) => value[..index].trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
+
+fn load_client_env_values() -> HashMap<String, String> {
+    load_client_env_values_with_overrides()
+        .map(|(values, _)| values)
+        .unwrap_or_default()
+}
+
+/// `Ok(None)` for a missing or unreadable file (skipped silently, as before), `Err` with
+/// the reason for a file over `MAX_CONFIG_FILE_BYTES`.
+fn read_config_file(path: &Path) -> Result<Option<String>, String> {
+    let Ok(file) = fs::File::open(path) else {
+        return Ok(None);
+    };
+    let too_large = || format!("larger than {MAX_CONFIG_FILE_BYTES} bytes");
+    if file
+        .metadata()
+        .is_ok_and(|metadata| metadata.len() > MAX_CONFIG_FILE_BYTES)
+    {
+        return Err(too_large());
+    }
+    // The metadata can be stale (or missing for special files), so the read is capped too.
+    let mut content = String::new();
+    match file
+        .take(MAX_CONFIG_FILE_BYTES + 1)
+        .read_to_string(&mut content)
+    {
+        Ok(read) if read as u64 > MAX_CONFIG_FILE_BYTES => Err(too_large()),
+        Ok(_) => Ok(Some(content)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Merges all candidate files and also reports each key defined in more than one file as
+/// `key_override KEY from <earlier> shadowed by <later>`, plus unresolved `${VAR}` references.
+/// A malformed `client.toml` is an error rather than being skipped.
+fn load_client_env_values_with_overrides() -> Result<(HashMap<String, String>, Vec<String>), String>
+{
+    let mut values = HashMap::new();
+    let mut origins: HashMap<String, PathBuf> = HashMap::new();
+    let mut overrides = Vec::new();
+
+    for file in candidate_client_env_files() {
+        let content = match read_config_file(&file) {
+            Ok(Some(content)) => content,
+            Ok(None) => continue,
+            Err(reason) => {
+                overrides.push(format!(
+                    "config_file_skipped path={} reason={reason}",
+                    file.display()
+                ));
+                continue;
+            }
+        };
+        let mut file_values = HashMap::new();
+        let is_toml = file
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"));
+        if is_toml {
+            parse_client_toml_file(&content, &mut file_values)
+                .map_err(|error| format!("'{}' is not valid TOML: {error}", file.display()))?;
+        } else {
+            overrides.extend(parse_client_env_file(&content, &mut file_values));
+        }
+
+        if file_values.len() > MAX_CONFIG_FILE_KEYS {
+            overrides.push(format!(
+                "config_file_skipped path={} reason=more than {MAX_CONFIG_FILE_KEYS} keys",
+                file.display()
+            ));
+            continue;
+        }
+
+        let mut keys: Vec<&String> = file_values.keys().collect();
+        keys.sort();
+        for key in keys {
+            let previous = origins.insert(key.clone(), file.clone());
+            // The cwd and exe-dir candidates can be the same file.
+            if let Some(previous) = previous.filter(|previous| *previous != file) {
+                overrides.push(format!(
+                    "key_override {key} from {} shadowed by {}",
+                    previous.display(),
+                    file.display()
+                ));
+            }
+        }
+        values.extend(file_values);
+    }
+
+    Ok((values, overrides))
+}
+
+/// Flattens `client.toml` into the `client.env` key map: `app_url = "..."` becomes
+/// `APP_URL`, arrays join with commas, and a `[staging]` table scopes its keys to that
+/// profile like `APP_URL.staging`.
+fn parse_client_toml_file(
+    content: &str,
+    output: &mut HashMap<String, String>,
+) -> Result<(), String> {
+    let table = content
+        .parse::<toml::Table>()
+        .map_err(|error| error.to_string())?;
+    for (key, value) in &table {
+        match value {
+            toml::Value::Table(profile_values) => {
+                for (profile_key, profile_value) in profile_values {
+                    let value = toml_scalar_value(profile_key, profile_value)?;
+                    output.insert(format!("{}.{key}", profile_key.to_ascii_uppercase()), value);
+                }
+            }
+            value => {
+                output.insert(key.to_ascii_uppercase(), toml_scalar_value(key, value)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn toml_scalar_value(key: &str, value: &toml::Value) -> Result<String, String> {
+    match value {
+        toml::Value::String(text) => Ok(text.clone()),
+        toml::Value::Integer(_)
+        | toml::Value::Float(_)
+        | toml::Value::Boolean(_)
+        | toml::Value::Datetime(_) => Ok(value.to_string()),
+        toml::Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                toml::Value::Array(_) | toml::Value::Table(_) => {
+                    Err(format!("'{key}' may only list plain values."))
+                }
+                item => toml_scalar_value(key, item),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|items| items.join(",")),
+        toml::Value::Table(_) => Err(format!(
+            "'{key}' is nested too deeply; only one level of profile tables is supported."
+        )),
+    }
+}
+
+fn split_profile_key(key: &str) -> Option<(&str, &str)> {
+    let (base, profile) = key.split_once('.')?;
+    if base.is_empty() || profile.trim().is_empty() {
+        return None;
+    }
+    Some((base, profile.trim()))
+}
+
+// Profile-scoped keys (`APP_URL.staging`) override their unscoped counterpart for the
+// active profile; scoped keys for other profiles are dropped.
+fn apply_profile_overrides(
+    file_values: HashMap<String, String>,
+    profile: Option<&str>,
+) -> (HashMap<String, String>, Vec<String>) {
+    let mut resolved = HashMap::new();
+    let mut scoped = Vec::new();
+
+    for (key, value) in file_values {
+        match split_profile_key(&key) {
+            Some((base, key_profile)) => {
+                if profile.is_some_and(|active| active.eq_ignore_ascii_case(key_profile)) {
+                    scoped.push((base.to_string(), value));
+                }
+            }
+            None => {
+                resolved.insert(key, value);
+            }
+        }
+    }
+
+    let mut overridden: Vec<String> = scoped.iter().map(|(key, _)| key.clone()).collect();
+    overridden.sort();
+    resolved.extend(scoped);
+
+    (resolved, overridden)
+}
+
+fn discover_profiles(file_values: &HashMap<String, String>) -> Vec<String> {
+    let mut profiles: Vec<String> = file_values
+        .keys()
+        .filter_map(|key| split_profile_key(key))
+        .map(|(_, profile)| profile.to_ascii_lowercase())
+        .collect();
+    profiles.sort();
+    profiles.dedup();
+    profiles
+}
+
+fn load_runtime_config(
+    profile_override: Option<&str>,
+) -> (Result<RuntimeConfig, ConfigError>, Vec<String>) {
+    let mut diagnostics = vec![
+        format!("timestamp={}", current_timestamp()),
+        format!("version={}", env!("CARGO_PKG_VERSION")),
+    ];
+
+    let data_dir = data_dir();
+    diagnostics.push(format!("data_dir_source={}", data_dir.source));
+    if let Some(error) = &data_dir.error {
+        return (Err(error.clone().into()), diagnostics);
+    }
+    match &data_dir.path {
+        Some(path) => {
+            if let Err(error) = fs::create_dir_all(path) {
+                return (
+                    Err(format!(
+                        "Data folder '{}' could not be created: {error}",
+                        path.display()
+                    )
+                    .into()),
+                    diagnostics,
+                );
+            }
+            diagnostics.push(format!("data_dir={}", path.display()));
+        }
+        None => diagnostics.push("data_dir=none (APPDATA is not set)".to_string()),
+    }
+
+    if let Err(error) = migrate_legacy_default_client_env_file() {
+        diagnostics.push(format!(
+            "migrate_legacy_default_client_env_file=error:{error}"
+        ));
+        return (Err(error.into()), diagnostics);
+    }
+
+    if let Err(error) = ensure_default_client_env_file() {
+        diagnostics.push(format!("ensure_default_client_env_file=error:{error}"));
+        return (Err(error.into()), diagnostics);
+    }
+
+    match cli_config_path() {
+        Ok(Some(path)) if !path.is_file() => {
+            let error = format!("--config file '{}' does not exist.", path.display());
+            diagnostics.push(format!("cli_config=missing:{}", path.display()));
+            return (Err(error.into()), diagnostics);
+        }
+        Ok(Some(path)) => diagnostics.push(format!("cli_config={}", path.display())),
+        Ok(None) => {}
+        Err(error) => {
+            diagnostics.push("cli_config=invalid".to_string());
+            return (Err(error.into()), diagnostics);
+        }
+    }
+
+    let (file_values, key_overrides) = match load_client_env_values_with_overrides() {
+        Ok(value) => value,
+        Err(error) => {
+            diagnostics.push("client_toml=invalid".to_string());
+            return (Err(error.into()), diagnostics);
+        }
+    };
+    diagnostics.extend(key_overrides);
+
+    let profile = match profile_override {
+        Some(name) => Some((name.to_string(), "switch_profile".to_string())),
+        None => read_optional_value("PROFILE", Some(ENV_PROFILE), &file_values),
+    };
+    let (file_values, profile_keys) =
+        apply_profile_overrides(file_values, profile.as_ref().map(|(name, _)| name.as_str()));
+    let profile = match profile {
+        Some((name, source)) => {
+            diagnostics.push(format!("active_profile={name} ({source})"));
+            if profile_keys.is_empty() {
+                diagnostics.push(format!(
+                    "profile_keys=none (no {name}-scoped keys, using unscoped values)"
+                ));
+            } else {
+                diagnostics.push(format!("profile_keys={}", profile_keys.join(",")));
+            }
+            Some(name)
+        }
+        None => {
+            diagnostics.push("active_profile=none".to_string());
+            None
+        }
+    };
+
+    // Read before anything that can fail, so those errors are already localized.
+    let locale = match read_optional_value("LOCALE", Some(ENV_LOCALE), &file_values) {
+        Some((raw, source)) => match Locale::parse(&raw) {
+            Some(locale) => {
+                diagnostics.push(format!("locale={} ({source})", locale.code()));
+                locale
+            }
+            None => {
+                diagnostics.push(format!("locale=invalid:{raw} ({source}, using en)"));
+                Locale::En
+            }
+        },
+        None => {
+            let locale = system_locale();
+            diagnostics.push(format!("locale={} (system)", locale.code()));
+            locale
+        }
+    };
+
+    let (app_url_raw, app_url_source) =
+        match read_required_value("APP_URL", Some(ENV_APP_URL), &file_values, locale) {
+            Ok(value) => value,
+            Err(error) => {
+                diagnostics.push(format!(
+                    "app_url_source=missing ({ENV_APP_URL} or APP_URL in client.env)"
+                ));
+                return (Err(ConfigError::Missing(error)), diagnostics);
+            }
+        };
+    diagnostics.push(format!("app_url_source={app_url_source}"));
+
+    // APP_URL may list failover targets after the primary: `primary,secondary,...`.
+    let mut app_url_entries = app_url_raw
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty());
+    let primary_app_url = app_url_entries.next().unwrap_or_default();
+    let failover_app_urls: Vec<&str> = app_url_entries.collect();
+
+    let mut app_url = match Url::parse(primary_app_url) {
+        Ok(value) => value,
+        Err(error) => {
+            return (
+                Err(tr(locale, "config.app_url_invalid")
+                    .replace("{error}", &error.to_string())
+                    .into()),
+                diagnostics,
+            )
+        }
+    };
+
+    if let Err(error) = check_app_url_scheme(&app_url, locale) {
+        return (Err(error.into()), diagnostics);
+    }
+
+    // Credentials must never reach the navigation script, the log, or AboutInfo; they are
+    // only kept to authenticate the reachability check.
+    let app_url_credentials = if !app_url.username().is_empty() || app_url.password().is_some() {
+        let username = percent_decode_str(app_url.username())
+            .decode_utf8_lossy()
+            .into_owned();
+        let password = app_url
+            .password()
+            .map(|value| percent_decode_str(value).decode_utf8_lossy().into_owned())
+            .unwrap_or_default();
+        let _ = app_url.set_username("");
+        let _ = app_url.set_password(None);
+        diagnostics.push("app_url_userinfo=stripped".to_string());
+        Some(format!("{username}:{password}"))
+    } else {
+        None
+    };
+
+    let normalized_app_host = match check_app_url_host(&app_url, locale) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+
+    let (allowed_hosts_raw, allowed_hosts_source) = match read_required_value(
+        "ALLOWED_HOSTS",
+        Some(ENV_ALLOWED_HOSTS),
+        &file_values,
+        locale,
+    ) {
+        Ok(value) => value,
+        Err(error) => {
+            diagnostics.push(format!(
+                "allowed_hosts_source=missing ({ENV_ALLOWED_HOSTS} or ALLOWED_HOSTS in client.env)"
+            ));
+            return (Err(ConfigError::Missing(error)), diagnostics);
+        }
+    };
+    diagnostics.push(format!("allowed_hosts_source={allowed_hosts_source}"));
+
+    let mut allowed_hosts: HashSet<String> = match allowed_hosts_raw
+        .split(',')
+        .filter(|value| !value.trim().is_empty())
+        .map(normalize_allowed_host_entry)
+        .collect()
+    {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+
+    let allowed_hosts_url = match read_optional_value(
+        "ALLOWED_HOSTS_URL",
+        Some(ENV_ALLOWED_HOSTS_URL),
+        &file_values,
+    ) {
+        Some((raw, source)) => match Url::parse(&raw) {
+            Ok(url) if url.scheme() == "https" => {
+                diagnostics.push(format!(
+                    "allowed_hosts_url={} ({source})",
+                    redacted_url(&url)
+                ));
+                Some(url)
+            }
+            _ => {
+                return (
+                    Err(format!("ALLOWED_HOSTS_URL must be an https:// URL, got '{raw}'.").into()),
+                    diagnostics,
+                )
+            }
+        },
+        None => None,
+    };
+
+    let (allow_localhost_release, allow_localhost_release_source) = match read_bool_value(
+        ENV_ALLOW_LOCALHOST_RELEASE,
+        Some(ENV_ALLOW_LOCALHOST_RELEASE),
+        false,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "localhost_release_override={} ({allow_localhost_release_source})",
+        allow_localhost_release
+    ));
+
+    let mut release_local_hosts: HashSet<String> = DEFAULT_RELEASE_LOCAL_HOSTS
+        .iter()
+        .map(|value| value.to_string())
+        .collect();
+    if let Some((raw, source)) = read_optional_value(
+        "RELEASE_LOCAL_HOSTS",
+        Some(ENV_RELEASE_LOCAL_HOSTS),
+        &file_values,
+    ) {
+        release_local_hosts.extend(
+            raw.split(',')
+                .map(normalize_host)
+                .filter(|value| !value.is_empty()),
+        );
+        diagnostics.push(format!("release_local_hosts_source={source}"));
+    }
+    diagnostics.push(format!(
+        "release_local_hosts={} (+127.0.0.0/8)",
+        sorted_entries(&release_local_hosts).join(",")
+    ));
+
+    let release_guard_hosts =
+        (!cfg!(debug_assertions) && !allow_localhost_release).then(|| release_local_hosts.clone());
+    if !cfg!(debug_assertions) {
+        if let Err(error) =
+            check_release_localhost_guard(&normalized_app_host, release_guard_hosts.as_ref())
+        {
+            diagnostics.push("release_localhost_guard=blocked".to_string());
+            return (Err(error.into()), diagnostics);
+        }
+        diagnostics.push("release_localhost_guard=pass".to_string());
+    } else {
+        diagnostics.push("release_localhost_guard=debug-skip".to_string());
+    }
+
+    let (window_title, window_title_source) =
+        read_optional_value("WINDOW_TITLE", Some(ENV_WINDOW_TITLE), &file_values).unwrap_or_else(
+            || {
+                (
+                    DEFAULT_TITLE.to_string(),
+                    format!("default {DEFAULT_TITLE}"),
+                )
+            },
+        );
+    diagnostics.push(format!("window_title_source={window_title_source}"));
+
+    let (window_width, window_width_source) = match parse_window_dimension(
+        "WINDOW_WIDTH",
+        Some(ENV_WINDOW_WIDTH),
+        DEFAULT_WIDTH,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!("window_width_source={window_width_source}"));
+
+    let (window_height, window_height_source) = match parse_window_dimension(
+        "WINDOW_HEIGHT",
+        Some(ENV_WINDOW_HEIGHT),
+        DEFAULT_HEIGHT,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!("window_height_source={window_height_source}"));
+
+    let (window_size_unit, window_size_unit_source) =
+        match read_optional_value("WINDOW_SIZE_UNIT", Some(ENV_WINDOW_SIZE_UNIT), &file_values) {
+            Some((raw, source)) => match raw.to_ascii_lowercase().as_str() {
+                "logical" => (WindowSizeUnit::Logical, source),
+                "physical" => (WindowSizeUnit::Physical, source),
+                _ => {
+                    return (
+                        Err(format!(
+                            "WINDOW_SIZE_UNIT must be 'logical' or 'physical', got '{raw}'."
+                        )
+                        .into()),
+                        diagnostics,
+                    )
+                }
+            },
+            None => (WindowSizeUnit::Logical, "default logical".to_string()),
+        };
+    diagnostics.push(format!(
+        "window_size_unit={} ({window_size_unit_source})",
+        window_size_unit.as_str()
+    ));
+
+    let (window_width, window_width_percent) = match window_width {
+        WindowDimension::Pixels(value) => (value, None),
+        WindowDimension::Percent(percent) => (DEFAULT_WIDTH, Some(percent)),
+    };
+    let (window_height, window_height_percent) = match window_height {
+        WindowDimension::Pixels(value) => (value, None),
+        WindowDimension::Percent(percent) => (DEFAULT_HEIGHT, Some(percent)),
+    };
+
+    let window_min_width = match parse_optional_window_dimension(
+        "WINDOW_MIN_WIDTH",
+        Some(ENV_WINDOW_MIN_WIDTH),
+        &file_values,
+    ) {
+        Ok(Some((value, source))) => {
+            diagnostics.push(format!("window_min_width={value} ({source})"));
+            Some(value)
+        }
+        Ok(None) => {
+            diagnostics.push("window_min_width=none".to_string());
+            None
+        }
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+
+    let window_min_height = match parse_optional_window_dimension(
+        "WINDOW_MIN_HEIGHT",
+        Some(ENV_WINDOW_MIN_HEIGHT),
+        &file_values,
+    ) {
+        Ok(Some((value, source))) => {
+            diagnostics.push(format!("window_min_height={value} ({source})"));
+            Some(value)
+        }
+        Ok(None) => {
+            diagnostics.push("window_min_height=none".to_string());
+            None
+        }
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+
+    let window_max_width = match parse_optional_window_dimension(
+        "WINDOW_MAX_WIDTH",
+        Some(ENV_WINDOW_MAX_WIDTH),
+        &file_values,
+    ) {
+        Ok(Some((value, source))) => {
+            diagnostics.push(format!("window_max_width={value} ({source})"));
+            Some(value)
+        }
+        Ok(None) => {
+            diagnostics.push("window_max_width=none".to_string());
+            None
+        }
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+
+    let window_max_height = match parse_optional_window_dimension(
+        "WINDOW_MAX_HEIGHT",
+        Some(ENV_WINDOW_MAX_HEIGHT),
+        &file_values,
+    ) {
+        Ok(Some((value, source))) => {
+            diagnostics.push(format!("window_max_height={value} ({source})"));
+            Some(value)
+        }
+        Ok(None) => {
+            diagnostics.push("window_max_height=none".to_string());
+            None
+        }
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+
+    let (window_zoom, window_zoom_source) =
+        match read_optional_value("WINDOW_ZOOM", Some(ENV_WINDOW_ZOOM), &file_values) {
+            Some((raw, source)) => match raw.parse::<f64>() {
+                Ok(value) if value.is_finite() && value > 0.0 => {
+                    let clamped = clamp_zoom(value);
+                    if clamped == value {
+                        (clamped, source)
+                    } else {
+                        (clamped, format!("{source} (clamped from {value})"))
+                    }
+                }
+                _ => {
+                    return (
+                        Err(format!("WINDOW_ZOOM must be a positive number, got '{raw}'.").into()),
+                        diagnostics,
+                    )
+                }
+            },
+            None => (
+                DEFAULT_WINDOW_ZOOM,
+                format!("default {DEFAULT_WINDOW_ZOOM}"),
+            ),
+        };
+    diagnostics.push(format!("window_zoom={window_zoom} ({window_zoom_source})"));
+
+    let window_icon_path =
+        match read_optional_value("WINDOW_ICON_PATH", Some(ENV_WINDOW_ICON_PATH), &file_values) {
+            Some((raw, source)) => {
+                let path = PathBuf::from(&raw);
+                let is_png = path
+                    .extension()
+                    .is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+                if !is_png {
+                    return (
+                        Err(
+                            format!("WINDOW_ICON_PATH must point to a .png file, got '{raw}'.")
+                                .into(),
+                        ),
+                        diagnostics,
+                    );
+                }
+                diagnostics.push(format!("window_icon_path={} ({source})", path.display()));
+                Some(path)
+            }
+            None => None,
+        };
+
+    // A bad color is cosmetic, so it falls back to the default instead of blocking startup.
+    let window_background = match read_optional_value(
+        "WINDOW_BACKGROUND",
+        Some(ENV_WINDOW_BACKGROUND),
+        &file_values,
+    ) {
+        Some((raw, source)) => match parse_hex_color(&raw) {
+            Some(color) => {
+                diagnostics.push(format!("window_background={color} ({source})"));
+                Some(color)
+            }
+            None => {
+                diagnostics.push(format!(
+                    "window_background=invalid:{raw} ({source}, using default)"
+                ));
+                None
+            }
+        },
+        None => None,
+    };
+
+    // Percentages and physical pixels only become logical sizes once the monitor is known,
+    // so those axes are clamped to the bounds at startup instead of rejected here.
+    let logical_pixels = window_size_unit == WindowSizeUnit::Logical;
+    let width_bounds = if logical_pixels && window_width_percent.is_none() {
+        validate_window_bounds("WIDTH", window_width, window_min_width, window_max_width)
+    } else {
+        Ok(())
+    };
+    if let Err(error) = width_bounds.and_then(|()| {
+        if logical_pixels && window_height_percent.is_none() {
+            validate_window_bounds(
+                "HEIGHT",
+                window_height,
+                window_min_height,
+                window_max_height,
+            )
+        } else {
+            Ok(())
+        }
+    }) {
+        return (Err(error.into()), diagnostics);
+    }
+
+    let (min_web_build_hash, min_web_build_hash_source) =
+        read_optional_value("MIN_WEB_BUILD_HASH", Some(ENV_MIN_WEB_BUILD_HASH), &file_values)
+            .map(|(value, source)| (Some(value), source))
+            .unwrap_or_else(|| (None, "not-set".to_string()));
+    diagnostics.push(format!("min_web_build_hash_source={min_web_build_hash_source}"));
+
+    let default_enforce_web_build = !cfg!(debug_assertions);
+    let (enforce_web_build, enforce_web_build_source) = match read_bool_value(
+        "ENFORCE_WEB_BUILD",
+        Some(ENV_ENFORCE_WEB_BUILD),
+        default_enforce_web_build,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "enforce_web_build={} ({enforce_web_build_source})",
+        enforce_web_build
+    ));
+
+    let (reachability_timeout_secs, reachability_timeout_source) = match parse_timeout_secs(
+        "REACHABILITY_TIMEOUT_SECS",
+        Some(ENV_REACHABILITY_TIMEOUT_SECS),
+        DEFAULT_REACHABILITY_TIMEOUT_SECS,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "reachability_timeout_source={reachability_timeout_source}"
+    ));
+
+    let (reachability_connect_timeout_secs, reachability_connect_timeout_source) =
+        match parse_timeout_secs(
+            "REACHABILITY_CONNECT_TIMEOUT_SECS",
+            Some(ENV_REACHABILITY_CONNECT_TIMEOUT_SECS),
+            DEFAULT_REACHABILITY_CONNECT_TIMEOUT_SECS,
+            &file_values,
+        ) {
+            // The connect phase is part of the total request, so it can never exceed it.
+            Ok((value, source)) if value > reachability_timeout_secs => (
+                reachability_timeout_secs,
+                format!("{source} (capped at REACHABILITY_TIMEOUT_SECS)"),
+            ),
+            Ok(value) => value,
+            Err(error) => return (Err(error.into()), diagnostics),
+        };
+    diagnostics.push(format!(
+        "reachability_timeouts connect={reachability_connect_timeout_secs}s ({reachability_connect_timeout_source}) total={reachability_timeout_secs}s"
+    ));
+
+    let (reachability_max_redirects, reachability_max_redirects_source) = match read_optional_value(
+        "REACHABILITY_MAX_REDIRECTS",
+        Some(ENV_REACHABILITY_MAX_REDIRECTS),
+        &file_values,
+    ) {
+        Some((raw, source)) => match raw.parse::<usize>() {
+            Ok(value) => (value, source),
+            Err(_) => {
+                return (
+                    Err(format!(
+                    "REACHABILITY_MAX_REDIRECTS must be a non-negative whole number, got '{raw}'."
+                )
+                    .into()),
+                    diagnostics,
+                )
+            }
+        },
+        None => (
+            DEFAULT_REACHABILITY_MAX_REDIRECTS,
+            format!("default {DEFAULT_REACHABILITY_MAX_REDIRECTS}"),
+        ),
+    };
+    diagnostics.push(match reachability_max_redirects {
+        0 => format!("reachability_redirect_policy=none ({reachability_max_redirects_source})"),
+        limit => format!(
+            "reachability_redirect_policy=limited:{limit} ({reachability_max_redirects_source})"
+        ),
+    });
+
+    let (retry_attempts, retry_attempts_source) = match parse_positive_integer(
+        "RETRY_ATTEMPTS",
+        Some(ENV_RETRY_ATTEMPTS),
+        DEFAULT_RETRY_ATTEMPTS,
+        &file_values,
+    ) {
+        Ok((value, source)) => (value.min(MAX_RETRY_ATTEMPTS) as u32, source),
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "retry_attempts={retry_attempts} ({retry_attempts_source})"
+    ));
+
+    let (startup_grace_secs, startup_grace_source) = match parse_positive_integer(
+        "STARTUP_GRACE_SECS",
+        Some(ENV_STARTUP_GRACE_SECS),
+        0,
+        &file_values,
+    ) {
+        Ok((value, source)) if value > MAX_STARTUP_GRACE_SECS => (
+            MAX_STARTUP_GRACE_SECS,
+            format!("{source} (clamped from {value})"),
+        ),
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "startup_grace_secs={startup_grace_secs} ({startup_grace_source})"
+    ));
+
+    let (bootstrap_max_secs, bootstrap_max_source) = match parse_positive_integer(
+        "BOOTSTRAP_MAX_SECS",
+        Some(ENV_BOOTSTRAP_MAX_SECS),
+        DEFAULT_BOOTSTRAP_MAX_SECS,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "bootstrap_max_secs={bootstrap_max_secs} ({bootstrap_max_source})"
+    ));
+
+    let health_check_path = read_optional_value(
+        "HEALTH_CHECK_PATH",
+        Some(ENV_HEALTH_CHECK_PATH),
+        &file_values,
+    );
+    let (reachability_url, health_check_path_source) = match &health_check_path {
+        Some((path, source)) => match health_check_url(&app_url, path) {
+            Ok(value) => (value, source.clone()),
+            Err(error) => return (Err(error.into()), diagnostics),
+        },
+        None => (app_url.clone(), "not-set".to_string()),
+    };
+    diagnostics.push(format!(
+        "health_check_path_source={health_check_path_source}"
+    ));
+
+    let (server_info_path, server_info_path_source) =
+        read_optional_value("SERVER_INFO_PATH", Some(ENV_SERVER_INFO_PATH), &file_values)
+            .unwrap_or_else(|| (DEFAULT_SERVER_INFO_PATH.to_string(), "default".to_string()));
+    if let Err(error) = server_info_url(&app_url, &server_info_path) {
+        return (Err(error.into()), diagnostics);
+    }
+    diagnostics.push(format!(
+        "server_info_path={server_info_path} ({server_info_path_source})"
+    ));
+
+    let health_expect_json_field = match read_optional_value(
+        "HEALTH_EXPECT_JSON_FIELD",
+        Some(ENV_HEALTH_EXPECT_JSON_FIELD),
+        &file_values,
+    ) {
+        Some((raw, source)) => match raw.split_once('=') {
+            Some((field, expected)) if !field.trim().is_empty() => {
+                let field = field.trim().to_string();
+                let expected = expected.trim().to_string();
+                diagnostics.push(format!(
+                    "health_expect_json_field={field}={expected} ({source})"
+                ));
+                Some((field, expected))
+            }
+            _ => {
+                return (
+                    Err(format!(
+                        "HEALTH_EXPECT_JSON_FIELD must look like 'field=value', got '{raw}'."
+                    )
+                    .into()),
+                    diagnostics,
+                )
+            }
+        },
+        None => None,
+    };
+
+    let (log_max_size_mb, log_max_size_source) = match parse_positive_integer(
+        "LOG_MAX_SIZE_MB",
+        Some(ENV_LOG_MAX_SIZE_MB),
+        DEFAULT_LOG_MAX_SIZE_MB,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "log_max_size_mb={log_max_size_mb} ({log_max_size_source})"
+    ));
+
+    let mut pinned_cert_sha256 = Vec::new();
+    if let Some((raw, _)) = read_optional_value(
+        "PINNED_CERT_SHA256",
+        Some(ENV_PINNED_CERT_SHA256),
+        &file_values,
+    ) {
+        for entry in raw
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        {
+            match parse_cert_pin(entry) {
+                Some(pin) => pinned_cert_sha256.push(pin),
+                None => {
+                    return (
+                        Err(format!(
+                            "PINNED_CERT_SHA256 entries must be SHA-256 fingerprints in hex or base64, got '{entry}'."
+                        ).into()),
+                        diagnostics,
+                    )
+                }
+            }
+        }
+    }
+    if pinned_cert_sha256.is_empty() {
+        diagnostics.push("cert_pinning=disabled".to_string());
+    } else {
+        diagnostics.push(format!(
+            "cert_pinning=enabled({} pins)",
+            pinned_cert_sha256.len()
+        ));
+    }
+
+    // Certificate, key and password material never reach the log; only `mtls=enabled` does.
+    let identity_sources = (
+        read_optional_value("CLIENT_CERT_PATH", Some(ENV_CLIENT_CERT_PATH), &file_values),
+        read_optional_value("CLIENT_KEY_PATH", Some(ENV_CLIENT_KEY_PATH), &file_values),
+        read_optional_value(
+            "CLIENT_IDENTITY_PATH",
+            Some(ENV_CLIENT_IDENTITY_PATH),
+            &file_values,
+        ),
+    );
+    let identity_paths = match identity_sources {
+        (None, None, None) => Vec::new(),
+        (Some((cert, _)), Some((key, _)), None) => {
+            vec![("CLIENT_CERT_PATH", cert), ("CLIENT_KEY_PATH", key)]
+        }
+        (None, None, Some((identity, _))) => vec![("CLIENT_IDENTITY_PATH", identity)],
+        _ => {
+            return (
+                Err(
+                    "Set either CLIENT_CERT_PATH and CLIENT_KEY_PATH together, or CLIENT_IDENTITY_PATH alone."
+                        .to_string()
+                        .into(),
+                ),
+                diagnostics,
+            )
+        }
+    };
+    let identity_password = read_optional_value(
+        "CLIENT_IDENTITY_PASSWORD",
+        Some(ENV_CLIENT_IDENTITY_PASSWORD),
+        &file_values,
+    )
+    .map(|(password, _)| password)
+    .unwrap_or_default();
+    let pkcs12_path = match identity_paths.as_slice() {
+        [("CLIENT_IDENTITY_PATH", path)] => Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .filter(|extension| {
+                extension.eq_ignore_ascii_case("p12") || extension.eq_ignore_ascii_case("pfx")
+            })
+            .map(|_| path.clone()),
+        _ => None,
+    };
+    let client_identity = if identity_paths.is_empty() {
+        diagnostics.push("mtls=disabled".to_string());
+        None
+    } else if let Some(path) = pkcs12_path {
+        let identity = fs::read(&path)
+            .map_err(|error| format!("CLIENT_IDENTITY_PATH '{path}' could not be read: {error}"))
+            .and_then(|der| {
+                parse_pkcs12_identity(&der, &identity_password)
+                    .map_err(|error| format!("Client certificate is invalid: {error}."))
+            });
+        match identity {
+            Ok(identity) => {
+                diagnostics.push("mtls=enabled (pkcs12)".to_string());
+                Some(identity)
+            }
+            Err(error) => return (Err(error.into()), diagnostics),
+        }
+    } else {
+        let mut pem = String::new();
+        for (key, path) in &identity_paths {
+            match fs::read(path) {
+                Ok(bytes) => match String::from_utf8(bytes) {
+                    Ok(text) => {
+                        pem.push_str(&text);
+                        pem.push('\n');
+                    }
+                    Err(_) => {
+                        return (
+                            Err(format!(
+                                "{key} must be a PEM file, or a PKCS#12 file named .p12 or .pfx."
+                            )
+                            .into()),
+                            diagnostics,
+                        )
+                    }
+                },
+                Err(error) => {
+                    return (
+                        Err(format!("{key} '{path}' could not be read: {error}").into()),
+                        diagnostics,
+                    )
+                }
+            }
+        }
+        match parse_client_identity(&pem) {
+            Ok(identity) => {
+                diagnostics.push("mtls=enabled".to_string());
+                Some(identity)
+            }
+            Err(error) => {
+                return (
+                    Err(format!("Client certificate is invalid: {error}.").into()),
+                    diagnostics,
+                )
+            }
         }
+    };
 
-        if let Some((key, value)) = trimmed.split_once('=') {
-            let key_trimmed = key.trim();
-            if key_trimmed.is_empty() {
-                continue;
+    let mut webview_headers = Vec::new();
+    if let Some((raw, source)) =
+        read_optional_value("WEBVIEW_HEADERS", Some(ENV_WEBVIEW_HEADERS), &file_values)
+    {
+        for entry in raw.split(';').filter(|entry| !entry.trim().is_empty()) {
+            match parse_header_line("WEBVIEW_HEADERS", entry) {
+                Ok(header) => webview_headers.push(header),
+                Err(error) => return (Err(error.into()), diagnostics),
             }
-
-            let cleaned_value = value
-                .trim()
-                .trim_matches('"')
-                .trim_matches('\'')
-                .to_string();
-
-            output.insert(key_trimmed.to_string(), cleaned_value);
         }
+        // Names only; values may carry credentials.
+        diagnostics.push(format!(
+            "webview_headers={} ({source})",
+            webview_headers
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>()
+                .join(",")
+        ));
     }
-}
 
-fn load_client_env_values() -> HashMap<String, String> {
-    let mut values = HashMap::new();
+    let mut reachability_auth_origin = None;
+    let reachability_auth_header = match read_optional_value(
+        "REACHABILITY_AUTH_HEADER",
+        Some(ENV_REACHABILITY_AUTH_HEADER),
+        &file_values,
+    ) {
+        Some((raw, source)) => match parse_header_line("REACHABILITY_AUTH_HEADER", &raw) {
+            Ok(header) => {
+                // Never log the header value; it usually carries a credential.
+                diagnostics.push(format!("reachability_auth_header=configured ({source})"));
+                Some(header)
+            }
+            Err(error) => return (Err(error.into()), diagnostics),
+        },
+        None => match app_url_credentials.as_deref().map(basic_auth_header) {
+            Some(Ok(header)) => {
+                diagnostics
+                    .push("reachability_auth_header=configured (APP_URL userinfo)".to_string());
+                reachability_auth_origin = Some(app_url.origin());
+                Some(header)
+            }
+            Some(Err(error)) => return (Err(error.into()), diagnostics),
+            None => {
+                diagnostics.push("reachability_auth_header=not-set".to_string());
+                None
+            }
+        },
+    };
 
-    for file in candidate_client_env_files() {
-        if let Ok(content) = fs::read_to_string(file) {
-            parse_client_env_file(&content, &mut values);
+    let proxy_url = match read_optional_value(
+        "HTTP_PROXY_URL",
+        Some(ENV_HTTP_PROXY_URL),
+        &file_values,
+    ) {
+        Some((raw, source)) => {
+            let parsed = Url::parse(&raw)
+                .map_err(|error| error.to_string())
+                .and_then(|url| {
+                    reqwest::Proxy::all(url.clone())
+                        .map(|_| url)
+                        .map_err(|error| error.to_string())
+                });
+            match parsed {
+                Ok(url) => {
+                    diagnostics.push(format!(
+                        "proxy=configured {} ({source})",
+                        redacted_url(&url)
+                    ));
+                    Some(url)
+                }
+                Err(error) => {
+                    return (
+                        Err(format!("HTTP_PROXY_URL must be a valid proxy URL: {error}").into()),
+                        diagnostics,
+                    )
+                }
+            }
         }
-    }
-
-    values
-}
+        None => {
+            let system_proxy = SYSTEM_PROXY_ENV_KEYS
+                .iter()
+                .find_map(|key| read_process_env_value(key).map(|value| (*key, value)));
+            match system_proxy {
+                Some((key, value)) => {
+                    if let Err(error) = reqwest::Proxy::all(value.as_str()) {
+                        return (
+                            Err(format!("{key} must be a valid proxy URL: {error}").into()),
+                            diagnostics,
+                        );
+                    }
+                    diagnostics.push(format!("proxy=configured (process env {key})"));
+                }
+                None => diagnostics.push("proxy=none".to_string()),
+            }
+            None
+        }
+    };
 
-fn load_runtime_config() -> (Result<RuntimeConfig, String>, Vec<String>) {
-    let mut diagnostics = vec![
-        format!("timestamp={}", current_timestamp()),
-        format!("version={}", env!("CARGO_PKG_VERSION")),
-    ];
+    // Downloaded only once proxy, pins and client certificate are parsed, so it goes out
+    // like every other request. A failed download falls back to the last good list, so a
+    // network blip does not shrink the allowlist; without a cache only ALLOWED_HOSTS applies.
+    if let Some(url) = &allowed_hosts_url {
+        let builder = http_client_builder(
+            Duration::from_secs(reachability_timeout_secs),
+            Duration::from_secs(reachability_connect_timeout_secs),
+            reachability_max_redirects,
+            proxy_url.as_ref(),
+            &pinned_cert_sha256,
+            client_identity.as_ref(),
+        );
+        match builder.and_then(|builder| fetch_remote_allowed_hosts(url, builder)) {
+            Ok(remote_hosts) => {
+                diagnostics.push(format!(
+                    "allowed_hosts_remote=fetched count={}",
+                    remote_hosts.len()
+                ));
+                save_allowed_hosts_cache(url, &remote_hosts);
+                allowed_hosts.extend(remote_hosts);
+            }
+            Err(error) => match load_allowed_hosts_cache(url) {
+                Some(cache) => {
+                    diagnostics.push(format!(
+                        "allowed_hosts_remote=cached count={} fetched_at={} error={error}",
+                        cache.hosts.len(),
+                        cache.fetched_at
+                    ));
+                    allowed_hosts.extend(cache.hosts);
+                }
+                None => {
+                    diagnostics.push(format!("allowed_hosts_remote=unavailable error={error}"));
+                }
+            },
+        }
+    }
 
-    if let Err(error) = migrate_legacy_default_client_env_file() {
-        diagnostics.push(format!(
-            "migrate_legacy_default_client_env_file=error:{error}"
-        ));
-        return (Err(error), diagnostics);
+    if allowed_hosts.is_empty() {
+        return (
+            Err(tr(locale, "config.allowed_hosts_empty").to_string().into()),
+            diagnostics,
+        );
     }
 
-    if let Err(error) = ensure_default_client_env_file() {
-        diagnostics.push(format!("ensure_default_client_env_file=error:{error}"));
-        return (Err(error), diagnostics);
+    if let Err(error) =
+        check_app_url_allowed(&normalized_app_host, &app_url, &allowed_hosts, locale)
+    {
+        return (Err(error.into()), diagnostics);
     }
 
-    let file_values = load_client_env_values();
+    let mut app_targets = vec![AppTarget {
+        app_url: app_url.clone(),
+        reachability_url: reachability_url.clone(),
+    }];
+    for (index, raw) in failover_app_urls.iter().enumerate() {
+        let target = parse_failover_app_url(raw, &allowed_hosts, release_guard_hosts.as_ref())
+            .and_then(|(url, userinfo_stripped)| {
+                if userinfo_stripped {
+                    diagnostics.push(format!("app_url_failover_{}_userinfo=stripped", index + 1));
+                }
+                let reachability_url = match &health_check_path {
+                    Some((path, _)) => health_check_url(&url, path)?,
+                    None => url.clone(),
+                };
+                Ok(AppTarget {
+                    app_url: url,
+                    reachability_url,
+                })
+            });
+        match target {
+            Ok(target) => {
+                diagnostics.push(format!("app_url_failover_{}={}", index + 1, target.app_url));
+                app_targets.push(target);
+            }
+            Err(error) => return (Err(error.into()), diagnostics),
+        }
+    }
+    diagnostics.push(format!("app_url_targets={}", app_targets.len()));
 
-    let (app_url_raw, app_url_source) =
-        match read_required_value("APP_URL", Some(ENV_APP_URL), &file_values) {
-            Ok(value) => value,
-            Err(error) => {
+    let update_manifest_url = match read_optional_value(
+        "UPDATE_MANIFEST_URL",
+        Some(ENV_UPDATE_MANIFEST_URL),
+        &file_values,
+    ) {
+        Some((raw, source)) => match Url::parse(&raw) {
+            Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {
                 diagnostics.push(format!(
-                    "app_url_source=missing ({ENV_APP_URL} or APP_URL in client.env)"
+                    "update_manifest_url={} ({source})",
+                    redacted_url(&url)
                 ));
-                return (Err(error), diagnostics);
+                Some(url)
+            }
+            _ => {
+                return (
+                    Err(
+                        format!("UPDATE_MANIFEST_URL must be an HTTP or HTTPS URL, got '{raw}'.")
+                            .into(),
+                    ),
+                    diagnostics,
+                )
             }
+        },
+        None => {
+            diagnostics.push("update_manifest_url=none".to_string());
+            None
+        }
+    };
+
+    let (minimize_to_tray, minimize_to_tray_source) = match read_bool_value(
+        "MINIMIZE_TO_TRAY",
+        Some(ENV_MINIMIZE_TO_TRAY),
+        false,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "minimize_to_tray={minimize_to_tray} ({minimize_to_tray_source})"
+    ));
+
+    let (kiosk_mode, kiosk_mode_source) =
+        match read_bool_value("KIOSK_MODE", Some(ENV_KIOSK_MODE), false, &file_values) {
+            Ok(value) => value,
+            Err(error) => return (Err(error.into()), diagnostics),
         };
-    diagnostics.push(format!("app_url_source={app_url_source}"));
+    diagnostics.push(format!("kiosk_mode={kiosk_mode} ({kiosk_mode_source})"));
 
-    let app_url = match Url::parse(&app_url_raw) {
+    let (disable_context_menu, disable_context_menu_source) = match read_bool_value(
+        "DISABLE_CONTEXT_MENU",
+        Some(ENV_DISABLE_CONTEXT_MENU),
+        false,
+        &file_values,
+    ) {
         Ok(value) => value,
-        Err(error) => {
-            return (
-                Err(format!("APP_URL must be a valid URL: {error}")),
-                diagnostics,
-            )
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "disable_context_menu={disable_context_menu} ({disable_context_menu_source})"
+    ));
+
+    let (disable_text_selection, disable_text_selection_source) = match read_bool_value(
+        "DISABLE_TEXT_SELECTION",
+        Some(ENV_DISABLE_TEXT_SELECTION),
+        false,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "disable_text_selection={disable_text_selection} ({disable_text_selection_source})"
+    ));
+
+    let (inactivity_reset_secs, inactivity_reset_source) = match parse_positive_integer(
+        "INACTIVITY_RESET_SECS",
+        Some(ENV_INACTIVITY_RESET_SECS),
+        0,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    let inactivity_reset =
+        (inactivity_reset_secs > 0).then(|| Duration::from_secs(inactivity_reset_secs));
+    diagnostics.push(match inactivity_reset {
+        Some(_) => {
+            format!("inactivity_reset_secs={inactivity_reset_secs} ({inactivity_reset_source})")
         }
+        None => "inactivity_reset_secs=none".to_string(),
+    });
+
+    let (reachability_poll_secs, reachability_poll_source) = match parse_positive_integer(
+        "REACHABILITY_POLL_SECS",
+        Some(ENV_REACHABILITY_POLL_SECS),
+        0,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
     };
+    let reachability_poll =
+        (reachability_poll_secs > 0).then(|| Duration::from_secs(reachability_poll_secs));
+    diagnostics.push(match reachability_poll {
+        Some(_) => {
+            format!("reachability_poll_secs={reachability_poll_secs} ({reachability_poll_source})")
+        }
+        None => "reachability_poll_secs=none".to_string(),
+    });
 
-    if app_url.scheme() != "http" && app_url.scheme() != "https" {
-        return (
-            Err("APP_URL must use HTTP or HTTPS.".to_string()),
-            diagnostics,
-        );
-    }
+    let (offline_title_suffix, offline_title_suffix_source) = read_optional_value(
+        "OFFLINE_TITLE_SUFFIX",
+        Some(ENV_OFFLINE_TITLE_SUFFIX),
+        &file_values,
+    )
+    .unwrap_or_else(|| {
+        (
+            DEFAULT_OFFLINE_TITLE_SUFFIX.to_string(),
+            format!("default {DEFAULT_OFFLINE_TITLE_SUFFIX}"),
+        )
+    });
+    diagnostics.push(format!(
+        "offline_title_suffix_source={offline_title_suffix_source}"
+    ));
+
+    let (single_instance, single_instance_source) = match read_bool_value(
+        "SINGLE_INSTANCE",
+        Some(ENV_SINGLE_INSTANCE),
+        true,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "single_instance={single_instance} ({single_instance_source})"
+    ));
+
+    let (enable_devtools, enable_devtools_source) = match read_bool_value(
+        "ENABLE_DEVTOOLS",
+        Some(ENV_ENABLE_DEVTOOLS),
+        false,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "devtools={} ({enable_devtools_source})",
+        if enable_devtools {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    ));
 
-    let app_host = match app_url.host_str() {
-        Some(value) => value,
-        None => return (Err("APP_URL must include a host.".to_string()), diagnostics),
+    let (precheck_allowed_hosts, precheck_allowed_hosts_source) = match read_bool_value(
+        "PRECHECK_ALLOWED_HOSTS",
+        Some(ENV_PRECHECK_ALLOWED_HOSTS),
+        false,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
     };
-    let normalized_app_host = normalize_host(app_host);
+    diagnostics.push(format!(
+        "precheck_allowed_hosts={precheck_allowed_hosts} ({precheck_allowed_hosts_source})"
+    ));
 
-    let (allowed_hosts_raw, allowed_hosts_source) =
-        match read_required_value("ALLOWED_HOSTS", Some(ENV_ALLOWED_HOSTS), &file_values) {
+    let (strict_https, strict_https_source) =
+        match read_bool_value("STRICT_HTTPS", Some(ENV_STRICT_HTTPS), false, &file_values) {
             Ok(value) => value,
-            Err(error) => {
-                diagnostics.push(format!(
-          "allowed_hosts_source=missing ({ENV_ALLOWED_HOSTS} or ALLOWED_HOSTS in client.env)"
-        ));
-                return (Err(error), diagnostics);
-            }
+            Err(error) => return (Err(error.into()), diagnostics),
         };
-    diagnostics.push(format!("allowed_hosts_source={allowed_hosts_source}"));
+    diagnostics.push(format!(
+        "strict_https={strict_https} ({strict_https_source})"
+    ));
 
-    let allowed_hosts: HashSet<String> = allowed_hosts_raw
-        .split(',')
-        .map(normalize_host)
-        .filter(|value| !value.is_empty())
-        .collect();
+    let (strict_allowed_hosts, strict_allowed_hosts_source) = match read_bool_value(
+        "STRICT_ALLOWED_HOSTS",
+        Some(ENV_STRICT_ALLOWED_HOSTS),
+        false,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "strict_allowed_hosts={strict_allowed_hosts} ({strict_allowed_hosts_source})"
+    ));
 
-    if allowed_hosts.is_empty() {
-        return (
-            Err("ALLOWED_HOSTS must include at least one host.".to_string()),
-            diagnostics,
-        );
-    }
+    let (block_data_urls, block_data_urls_source) = match read_bool_value(
+        "BLOCK_DATA_URLS",
+        Some(ENV_BLOCK_DATA_URLS),
+        false,
+        &file_values,
+    ) {
+        Ok(value) => value,
+        Err(error) => return (Err(error.into()), diagnostics),
+    };
+    diagnostics.push(format!(
+        "block_data_urls={block_data_urls} ({block_data_urls_source})"
+    ));
 
-    if !allowed_hosts.contains(&normalized_app_host) {
-        return (
-            Err("ALLOWED_HOSTS must include the APP_URL host.".to_string()),
-            diagnostics,
-        );
-    }
+    let (disable_gpu, disable_gpu_source) =
+        match read_bool_value("DISABLE_GPU", Some(ENV_DISABLE_GPU), false, &file_values) {
+            Ok(value) => value,
+            Err(error) => return (Err(error.into()), diagnostics),
+        };
+    diagnostics.push(format!("disable_gpu={disable_gpu} ({disable_gpu_source})"));
 
-    let (allow_localhost_release, allow_localhost_release_source) = match read_bool_value(
-        ENV_ALLOW_LOCALHOST_RELEASE,
-        Some(ENV_ALLOW_LOCALHOST_RELEASE),
+    let (extra_webview_args, extra_webview_args_source) = match read_optional_value(
+        "EXTRA_WEBVIEW_ARGS",
+        Some(ENV_EXTRA_WEBVIEW_ARGS),
+        &file_values,
+    ) {
+        Some((raw, source)) => match parse_extra_webview_args(&raw) {
+            Ok(args) => (args, source),
+            Err(error) => {
+                diagnostics.push(format!("extra_webview_args={raw} ({source})"));
+                return (Err(error.into()), diagnostics);
+            }
+        },
+        None => (Vec::new(), "default".to_string()),
+    };
+    diagnostics.push(format!(
+        "extra_webview_args={} ({extra_webview_args_source})",
+        extra_webview_args.join(" ")
+    ));
+
+    let (audit_navigation, audit_navigation_source) = match read_bool_value(
+        "AUDIT_NAVIGATION",
+        Some(ENV_AUDIT_NAVIGATION),
         false,
         &file_values,
     ) {
         Ok(value) => value,
-        Err(error) => return (Err(error), diagnostics),
+        Err(error) => return (Err(error.into()), diagnostics),
     };
     diagnostics.push(format!(
-        "localhost_release_override={} ({allow_localhost_release_source})",
-        allow_localhost_release
+        "audit_navigation={audit_navigation} ({audit_navigation_source})"
     ));
 
-    if !cfg!(debug_assertions) {
-        let blocked_release_localhost = matches!(
-            normalized_app_host.as_str(),
-            "localhost" | "127.0.0.1" | "::1" | "tauri.localhost"
-        );
-        if blocked_release_localhost && !allow_localhost_release {
-            diagnostics.push("release_localhost_guard=blocked".to_string());
-            return (
-        Err(
-          "APP_URL host resolves to localhost in release build. Use a non-localhost target, or set CRA_CLIENT_ALLOW_LOCALHOST_RELEASE=true for diagnostic builds."
-            .to_string(),
-        ),
-        diagnostics,
-      );
-        }
-        diagnostics.push("release_localhost_guard=pass".to_string());
-    } else {
-        diagnostics.push("release_localhost_guard=debug-skip".to_string());
-    }
-
-    let (window_title, window_title_source) =
-        read_optional_value("WINDOW_TITLE", Some(ENV_WINDOW_TITLE), &file_values).unwrap_or_else(
-            || {
-                (
-                    DEFAULT_TITLE.to_string(),
-                    format!("default {DEFAULT_TITLE}"),
-                )
+    let (log_json, log_format_source) =
+        match read_optional_value("LOG_FORMAT", Some(ENV_LOG_FORMAT), &file_values) {
+            Some((raw, source)) => match raw.to_ascii_lowercase().as_str() {
+                "text" => (false, source),
+                "json" => (true, source),
+                _ => {
+                    return (
+                        Err(format!("LOG_FORMAT must be 'text' or 'json', got '{raw}'.").into()),
+                        diagnostics,
+                    )
+                }
             },
-        );
-    diagnostics.push(format!("window_title_source={window_title_source}"));
+            None => (false, "default text".to_string()),
+        };
+    diagnostics.push(format!(
+        "log_format={} ({log_format_source})",
+        if log_json { "json" } else { "text" }
+    ));
 
-    let (window_width, window_width_source) = match parse_window_dimension(
-        "WINDOW_WIDTH",
-        Some(ENV_WINDOW_WIDTH),
-        DEFAULT_WIDTH,
+    let (reachability_head, reachability_method_source) = match read_optional_value(
+        "REACHABILITY_METHOD",
+        Some(ENV_REACHABILITY_METHOD),
         &file_values,
     ) {
-        Ok(value) => value,
-        Err(error) => return (Err(error), diagnostics),
+        Some((raw, source)) => match raw.to_ascii_uppercase().as_str() {
+            "HEAD" => (true, source),
+            "GET" => (false, source),
+            _ => {
+                return (
+                    Err(
+                        format!("REACHABILITY_METHOD must be 'HEAD' or 'GET', got '{raw}'.").into(),
+                    ),
+                    diagnostics,
+                )
+            }
+        },
+        None => (true, "default HEAD".to_string()),
     };
-    diagnostics.push(format!("window_width_source={window_width_source}"));
+    diagnostics.push(format!(
+        "reachability_method={} ({reachability_method_source})",
+        if reachability_head { "HEAD" } else { "GET" }
+    ));
 
-    let (window_height, window_height_source) = match parse_window_dimension(
-        "WINDOW_HEIGHT",
-        Some(ENV_WINDOW_HEIGHT),
-        DEFAULT_HEIGHT,
+    let (retry_on_gateway_errors, retry_on_gateway_errors_source) = match read_bool_value(
+        "RETRY_ON_GATEWAY_ERRORS",
+        Some(ENV_RETRY_ON_GATEWAY_ERRORS),
+        false,
         &file_values,
     ) {
         Ok(value) => value,
-        Err(error) => return (Err(error), diagnostics),
+        Err(error) => return (Err(error.into()), diagnostics),
     };
-    diagnostics.push(format!("window_height_source={window_height_source}"));
+    diagnostics.push(format!(
+        "retry_on_gateway_errors={retry_on_gateway_errors} ({retry_on_gateway_errors_source})"
+    ));
 
-    let (min_web_build_hash, min_web_build_hash_source) =
-        read_optional_value("MIN_WEB_BUILD_HASH", Some(ENV_MIN_WEB_BUILD_HASH), &file_values)
-            .map(|(value, source)| (Some(value), source))
-            .unwrap_or_else(|| (None, "not-set".to_string()));
-    diagnostics.push(format!("min_web_build_hash_source={min_web_build_hash_source}"));
+    let external_schemes =
+        match read_optional_value("EXTERNAL_SCHEMES", Some(ENV_EXTERNAL_SCHEMES), &file_values) {
+            Some((raw, source)) => match parse_external_schemes(&raw) {
+                Ok(value) => {
+                    diagnostics.push(format!(
+                        "external_schemes={} ({source})",
+                        sorted_entries(&value).join(",")
+                    ));
+                    value
+                }
+                Err(error) => return (Err(error.into()), diagnostics),
+            },
+            None => {
+                diagnostics.push("external_schemes=none".to_string());
+                HashSet::new()
+            }
+        };
 
-    let default_enforce_web_build = !cfg!(debug_assertions);
-    let (enforce_web_build, enforce_web_build_source) = match read_bool_value(
-        "ENFORCE_WEB_BUILD",
-        Some(ENV_ENFORCE_WEB_BUILD),
-        default_enforce_web_build,
+    let reachable_status_codes = match read_optional_value(
+        "REACHABLE_STATUS_CODES",
+        Some(ENV_REACHABLE_STATUS_CODES),
+        &file_values,
+    ) {
+        Some((raw, source)) => match parse_reachable_status_codes(&raw) {
+            Ok(value) => {
+                diagnostics.push(format!(
+                    "reachable_status_codes={} ({source})",
+                    value
+                        .iter()
+                        .map(u16::to_string)
+                        .collect::<Vec<_>>()
+                        .join(",")
+                ));
+                value
+            }
+            Err(error) => return (Err(error.into()), diagnostics),
+        },
+        None => BTreeSet::new(),
+    };
+
+    let (allow_https_downgrade, allow_https_downgrade_source) = match read_bool_value(
+        "ALLOW_HTTPS_DOWNGRADE",
+        Some(ENV_ALLOW_HTTPS_DOWNGRADE),
+        false,
         &file_values,
     ) {
         Ok(value) => value,
-        Err(error) => return (Err(error), diagnostics),
+        Err(error) => return (Err(error.into()), diagnostics),
     };
     diagnostics.push(format!(
-        "enforce_web_build={} ({enforce_web_build_source})",
-        enforce_web_build
+        "allow_https_downgrade={allow_https_downgrade} ({allow_https_downgrade_source})"
     ));
+    if !allow_https_downgrade {
+        let https_seen_hosts = load_https_seen_hosts();
+        let downgraded = app_targets.iter().find_map(|target| {
+            target
+                .app_url
+                .host_str()
+                .map(normalize_host)
+                .filter(|host| target.app_url.scheme() == "http" && https_seen_hosts.contains(host))
+        });
+        if let Some(host) = downgraded {
+            return (
+                Err(format!(
+                    "APP_URL uses http for {host}, which was previously reached over https. Switch APP_URL to https or set ALLOW_HTTPS_DOWNGRADE=true."
+                ).into()),
+                diagnostics,
+            );
+        }
+    }
+
+    let value_sources = BTreeMap::from([
+        ("APP_URL", app_url_source),
+        ("ALLOWED_HOSTS", allowed_hosts_source),
+        ("ALLOW_LOCALHOST_RELEASE", allow_localhost_release_source),
+        ("WINDOW_TITLE", window_title_source),
+        ("WINDOW_WIDTH", window_width_source),
+        ("WINDOW_HEIGHT", window_height_source),
+        ("WINDOW_SIZE_UNIT", window_size_unit_source),
+        ("OFFLINE_TITLE_SUFFIX", offline_title_suffix_source),
+        ("WINDOW_ZOOM", window_zoom_source),
+        ("MIN_WEB_BUILD_HASH", min_web_build_hash_source),
+        ("ENFORCE_WEB_BUILD", enforce_web_build_source),
+        ("REACHABILITY_TIMEOUT_SECS", reachability_timeout_source),
+        (
+            "REACHABILITY_CONNECT_TIMEOUT_SECS",
+            reachability_connect_timeout_source,
+        ),
+        (
+            "REACHABILITY_MAX_REDIRECTS",
+            reachability_max_redirects_source,
+        ),
+        ("RETRY_ATTEMPTS", retry_attempts_source),
+        ("STARTUP_GRACE_SECS", startup_grace_source),
+        ("BOOTSTRAP_MAX_SECS", bootstrap_max_source),
+        ("HEALTH_CHECK_PATH", health_check_path_source),
+        ("SERVER_INFO_PATH", server_info_path_source),
+        ("LOG_MAX_SIZE_MB", log_max_size_source),
+        ("MINIMIZE_TO_TRAY", minimize_to_tray_source),
+        ("KIOSK_MODE", kiosk_mode_source),
+        ("DISABLE_CONTEXT_MENU", disable_context_menu_source),
+        ("DISABLE_TEXT_SELECTION", disable_text_selection_source),
+        ("SINGLE_INSTANCE", single_instance_source),
+        ("ENABLE_DEVTOOLS", enable_devtools_source),
+        ("PRECHECK_ALLOWED_HOSTS", precheck_allowed_hosts_source),
+        ("STRICT_HTTPS", strict_https_source),
+        ("STRICT_ALLOWED_HOSTS", strict_allowed_hosts_source),
+        ("BLOCK_DATA_URLS", block_data_urls_source),
+        ("DISABLE_GPU", disable_gpu_source),
+        ("EXTRA_WEBVIEW_ARGS", extra_webview_args_source),
+        ("ALLOW_HTTPS_DOWNGRADE", allow_https_downgrade_source),
+        ("AUDIT_NAVIGATION", audit_navigation_source),
+        ("LOG_FORMAT", log_format_source),
+        ("REACHABILITY_METHOD", reachability_method_source),
+        ("RETRY_ON_GATEWAY_ERRORS", retry_on_gateway_errors_source),
+    ]);
 
     diagnostics.push(format!("resolved_app_url={app_url}"));
     diagnostics.push(format!("resolved_allowed_hosts={}", {
@@ -928,69 +7211,454 @@ fn load_runtime_config() -> (Result<RuntimeConfig, String>, Vec<String>) {
 
     (
         Ok(RuntimeConfig {
+            profile,
+            locale,
             app_url,
+            reachability_url,
+            app_targets,
+            health_check_path: health_check_path.map(|(path, _)| path),
+            server_info_path,
             allowed_hosts,
+            allowed_hosts_url,
+            release_guard_hosts,
             window_title,
             window_width,
             window_height,
+            window_width_percent,
+            window_height_percent,
+            window_size_unit,
+            window_min_width,
+            window_min_height,
+            window_max_width,
+            window_max_height,
+            window_zoom,
+            window_icon_path,
+            window_background,
             min_web_build_hash,
             enforce_web_build,
+            reachability_timeout: Duration::from_secs(reachability_timeout_secs),
+            reachability_connect_timeout: Duration::from_secs(reachability_connect_timeout_secs),
+            reachability_max_redirects,
+            reachability_head,
+            retry_on_gateway_errors,
+            health_expect_json_field,
+            startup_grace: Duration::from_secs(startup_grace_secs),
+            bootstrap_max: Duration::from_secs(bootstrap_max_secs),
+            inactivity_reset,
+            reachability_poll,
+            offline_title_suffix,
+            retry_attempts,
+            log_max_bytes: log_max_size_mb.saturating_mul(1024 * 1024),
+            pinned_cert_sha256,
+            client_identity,
+            reachability_auth_header,
+            reachability_auth_origin,
+            webview_headers,
+            proxy_url,
+            update_manifest_url,
+            minimize_to_tray,
+            kiosk_mode,
+            disable_context_menu,
+            disable_text_selection,
+            single_instance,
+            enable_devtools,
+            precheck_allowed_hosts,
+            strict_https,
+            strict_allowed_hosts,
+            block_data_urls,
+            disable_gpu,
+            extra_webview_args,
+            allow_https_downgrade,
+            audit_navigation,
+            log_json,
+            external_schemes,
+            reachable_status_codes,
+            value_sources,
         }),
         diagnostics,
     )
 }
 
+// The primary APP_URL checks, shared by `load_runtime_config` and `validate_app_url`.
+fn check_app_url_scheme(url: &Url, locale: Locale) -> Result<(), String> {
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(tr(locale, "config.app_url_scheme").to_string());
+    }
+    Ok(())
+}
+
+fn check_app_url_host(url: &Url, locale: Locale) -> Result<String, String> {
+    url.host_str()
+        .map(normalize_host)
+        .ok_or_else(|| tr(locale, "config.app_url_host").to_string())
+}
+
+fn check_app_url_allowed(
+    host: &str,
+    url: &Url,
+    allowed_hosts: &HashSet<String>,
+    locale: Locale,
+) -> Result<(), String> {
+    if !is_host_allowed(host, url.port_or_known_default(), allowed_hosts) {
+        return Err(tr(locale, "config.allowed_hosts_app_host").to_string());
+    }
+    Ok(())
+}
+
+fn check_release_localhost_guard(
+    host: &str,
+    release_guard_hosts: Option<&HashSet<String>>,
+) -> Result<(), String> {
+    if release_guard_hosts.is_some_and(|local_hosts| is_release_local_host(host, local_hosts)) {
+        return Err("APP_URL host resolves to localhost in release build. Use a non-localhost target, or set CRA_CLIENT_ALLOW_LOCALHOST_RELEASE=true for diagnostic builds.".to_string());
+    }
+    Ok(())
+}
+
+/// Validates a failover `APP_URL` entry the same way as the primary: HTTP(S), allowlisted
+/// host, and (when `release_guard_hosts` is given) not a local host. Userinfo is stripped.
+fn parse_failover_app_url(
+    raw: &str,
+    allowed_hosts: &HashSet<String>,
+    release_guard_hosts: Option<&HashSet<String>>,
+) -> Result<(Url, bool), String> {
+    let mut url = Url::parse(raw)
+        .map_err(|error| format!("APP_URL failover entry '{raw}' must be a valid URL: {error}"))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(format!(
+            "APP_URL failover entry '{raw}' must use HTTP or HTTPS."
+        ));
+    }
+
+    let userinfo_stripped = !url.username().is_empty() || url.password().is_some();
+    if userinfo_stripped {
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+    }
+
+    let host = url
+        .host_str()
+        .map(normalize_host)
+        .ok_or_else(|| "APP_URL failover entries must include a host.".to_string())?;
+    if !is_host_allowed(&host, url.port_or_known_default(), allowed_hosts) {
+        return Err(format!(
+            "ALLOWED_HOSTS must include the APP_URL failover host {host} and port."
+        ));
+    }
+    if release_guard_hosts.is_some_and(|local_hosts| is_release_local_host(&host, local_hosts)) {
+        return Err(format!(
+            "APP_URL failover host {host} resolves to localhost in release build."
+        ));
+    }
+
+    Ok((url, userinfo_stripped))
+}
+
+fn hand_off_external_url(app: &AppHandle, url: &Url) {
+    let result = tauri::api::shell::open(&app.shell_scope(), url.as_str(), None);
+    append_startup_log_entry(&format!(
+        "external_scheme_handoff timestamp={} scheme={} url={} result={}",
+        current_timestamp(),
+        url.scheme(),
+        url,
+        match result {
+            Ok(()) => "ok".to_string(),
+            Err(error) => format!("error:{error}"),
+        }
+    ));
+}
+
+fn parse_external_schemes(raw: &str) -> Result<HashSet<String>, String> {
+    let mut schemes = HashSet::new();
+    for entry in raw
+        .split(',')
+        .map(normalize_host)
+        .filter(|value| !value.is_empty())
+    {
+        let scheme = entry.trim_end_matches(':').to_string();
+        let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+        if !valid {
+            return Err(format!(
+                "EXTERNAL_SCHEMES entries must be URL schemes like 'mailto', got '{entry}'."
+            ));
+        }
+        if matches!(
+            scheme.as_str(),
+            "http" | "https" | "tauri" | "asset" | "about" | "data" | "blob" | "file"
+        ) {
+            return Err(format!(
+                "EXTERNAL_SCHEMES cannot include the '{scheme}' scheme."
+            ));
+        }
+        schemes.insert(scheme);
+    }
+    Ok(schemes)
+}
+
+/// Extra statuses treated as reachable on top of 2xx, 3xx, 401 and 403, e.g. a 503 a
+/// server returns while it is still starting.
+fn parse_reachable_status_codes(raw: &str) -> Result<BTreeSet<u16>, String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|entry| {
+            entry
+                .parse::<u16>()
+                .ok()
+                .filter(|code| (100..=599).contains(code))
+                .ok_or_else(|| {
+                    format!(
+                        "REACHABLE_STATUS_CODES entries must be HTTP status codes (100-599), got '{entry}'."
+                    )
+                })
+        })
+        .collect()
+}
+
 fn is_internal_navigation_host(host: &str) -> bool {
     matches!(host, "tauri.localhost" | "localhost" | "127.0.0.1" | "::1")
 }
 
-fn is_allowed_navigation(url: &Url, allowed_hosts: &HashSet<String>) -> bool {
+/// With `strict`, only the bundled shell host bypasses the allowlist; other local hosts
+/// must be listed in `ALLOWED_HOSTS` like any remote one.
+fn is_allowed_navigation(
+    url: &Url,
+    allowed_hosts: &HashSet<String>,
+    strict: bool,
+    block_data_urls: bool,
+) -> Result<(), NavigationBlock> {
     match url.scheme() {
-        "tauri" | "asset" | "about" | "data" | "blob" => true,
-        "http" | "https" => url
+        "tauri" | "asset" | "about" => Ok(()),
+        "data" | "blob" if !block_data_urls => Ok(()),
+        "http" | "https" => {
+            let allowed = url
+                .host_str()
+                .map(normalize_host)
+                .map(|host| {
+                    let internal = if strict {
+                        host == "tauri.localhost"
+                    } else {
+                        is_internal_navigation_host(&host)
+                    };
+                    internal || is_host_allowed(&host, url.port_or_known_default(), allowed_hosts)
+                })
+                .unwrap_or(false);
+            if allowed {
+                Ok(())
+            } else {
+                Err(NavigationBlock::Host)
+            }
+        }
+        _ => Err(NavigationBlock::Scheme),
+    }
+}
+
+/// Why `on_navigation` rejected a URL, logged as `reason=...`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum NavigationBlock {
+    /// Not http(s) or one of the bundled schemes, e.g. `file://`, or `data:` / `blob:`
+    /// under `BLOCK_DATA_URLS`.
+    Scheme,
+    /// http(s) to a host (or port) outside `ALLOWED_HOSTS`.
+    Host,
+    /// https to http, under `STRICT_HTTPS` or for a host already seen over https.
+    Downgrade,
+}
+
+impl NavigationBlock {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Scheme => "scheme",
+            Self::Host => "host",
+            Self::Downgrade => "downgrade",
+        }
+    }
+}
+
+/// `STRICT_HTTPS`: an https `APP_URL` may not lead to http pages, except the bundled
+/// local hosts. Upgrades from http to https are always fine.
+fn is_blocked_downgrade(strict_https: bool, app_url: &Url, url: &Url) -> bool {
+    strict_https
+        && app_url.scheme() == "https"
+        && url.scheme() == "http"
+        && !url
             .host_str()
             .map(normalize_host)
-            .map(|host| is_internal_navigation_host(&host) || allowed_hosts.contains(&host))
-            .unwrap_or(false),
-        _ => false,
+            .is_some_and(|host| is_internal_navigation_host(&host))
+}
+
+fn build_system_tray() -> SystemTray {
+    let menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new(TRAY_SHOW, "Show Window"))
+        .add_item(CustomMenuItem::new(TRAY_RELOAD, "Reload"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(TRAY_QUIT, "Quit"));
+    SystemTray::new().with_menu(menu)
+}
+
+fn focus_window(window: &Window) {
+    let _ = window.show();
+    let _ = window.unminimize();
+    let _ = window.set_focus();
+}
+
+fn single_instance_port_path() -> Option<PathBuf> {
+    appdata_dir_path().map(|path| path.join("instance.port"))
+}
+
+/// Asks an already running client to focus its window. Returns false when nothing answers
+/// with the expected reply, which covers a stale port file left by a crashed instance.
+fn focus_running_instance() -> bool {
+    let Some(port) = single_instance_port_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| raw.trim().parse::<u16>().ok())
+    else {
+        return false;
+    };
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&address, SINGLE_INSTANCE_TIMEOUT) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(SINGLE_INSTANCE_TIMEOUT));
+    let mut reply = [0_u8; SINGLE_INSTANCE_ACK.len()];
+    stream.write_all(SINGLE_INSTANCE_FOCUS_MESSAGE).is_ok()
+        && stream.read_exact(&mut reply).is_ok()
+        && reply[..] == *SINGLE_INSTANCE_ACK
+}
+
+/// Listens on a loopback port recorded under AppData so later launches can hand over to
+/// this process instead of opening a second window.
+fn start_single_instance_listener(app: AppHandle) -> Result<(), String> {
+    let path = single_instance_port_path().ok_or_else(|| {
+        "Could not resolve the instance port file: APPDATA is not set.".to_string()
+    })?;
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .map_err(|error| format!("Could not bind single-instance listener: {error}"))?;
+    let port = listener
+        .local_addr()
+        .map_err(|error| format!("Could not read single-instance listener port: {error}"))?
+        .port();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Could not create '{}': {error}", parent.display()))?;
+    }
+    fs::write(&path, port.to_string())
+        .map_err(|error| format!("Could not write '{}': {error}", path.display()))?;
+
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let _ = stream.set_read_timeout(Some(SINGLE_INSTANCE_TIMEOUT));
+            let mut message = [0_u8; SINGLE_INSTANCE_FOCUS_MESSAGE.len()];
+            if stream.read_exact(&mut message).is_err()
+                || message[..] != *SINGLE_INSTANCE_FOCUS_MESSAGE
+            {
+                continue;
+            }
+            let _ = stream.write_all(SINGLE_INSTANCE_ACK);
+            append_startup_log_entry("single_instance=focus_requested");
+            if let Some(window) = app.get_window("main") {
+                focus_window(&window);
+            }
+        }
+    });
+    append_startup_log_entry(&format!("single_instance=listening port={port}"));
+    Ok(())
+}
+
+fn handle_system_tray_event(app: &AppHandle, event: SystemTrayEvent) {
+    let Some(window) = app.get_window("main") else {
+        return;
+    };
+
+    match event {
+        SystemTrayEvent::LeftClick { .. } => {
+            if window.is_visible().unwrap_or(false) {
+                let _ = window.hide();
+            } else {
+                focus_window(&window);
+            }
+        }
+        SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
+            TRAY_SHOW => focus_window(&window),
+            TRAY_RELOAD => {
+                let result = get_config(&app.state::<AppState>())
+                    .and_then(|config| reload_window(&window, &config));
+                if let Err(error) = result {
+                    append_startup_log_entry(&format!("tray_reload=error:{error}"));
+                }
+            }
+            TRAY_QUIT => {
+                save_window_geometry(&window);
+                app.exit(0);
+            }
+            _ => {}
+        },
+        _ => {}
     }
 }
 
 fn main() {
-    let (runtime_config_result, startup_diagnostics) = load_runtime_config();
+    if let Some(mode) = cli_mode() {
+        std::process::exit(run_cli_mode(mode));
+    }
+
+    let (runtime_config_result, startup_diagnostics) = load_runtime_config(None);
+    if let Ok(config) = &runtime_config_result {
+        apply_log_settings(config);
+    }
 
     append_startup_log_entry("----- CRA Client startup -----");
     for entry in &startup_diagnostics {
         append_startup_log_entry(entry);
     }
 
-    let app_state = match runtime_config_result {
-        Ok(config) => {
-            append_startup_log_entry("startup_result=ok");
-            AppState {
-                config: Some(config),
-                config_error: None,
-            }
-        }
-        Err(error) => {
-            append_startup_log_entry(&format!("startup_result=error:{error}"));
-            AppState {
-                config: None,
-                config_error: Some(error),
-            }
-        }
-    };
+    // An invalid config still defaults to one instance, so the error window is not doubled.
+    let single_instance = !matches!(&runtime_config_result, Ok(config) if !config.single_instance);
+    if single_instance && focus_running_instance() {
+        append_startup_log_entry("single_instance=handed_off_to_running_instance");
+        return;
+    }
+
+    if let Ok(config) = &runtime_config_result {
+        apply_webview_environment(config);
+    }
+
+    let app_state = AppState::new(runtime_snapshot_from(
+        runtime_config_result,
+        startup_diagnostics,
+        "startup_result",
+    ));
 
     tauri::Builder::default()
         .manage(app_state)
-        .setup(|app| {
+        .setup(move |app| {
             let state = app.state::<AppState>();
-            let config = state.config.clone();
+            let RuntimeSnapshot {
+                config,
+                reachability_client,
+                ..
+            } = state.snapshot();
+            let app_handle = app.handle();
 
             let window_title = config
                 .as_ref()
-                .map(|value| value.window_title.clone())
+                .map(render_window_title)
                 .unwrap_or_else(|| DEFAULT_TITLE.to_string());
+            let kiosk_mode = config.as_ref().is_some_and(|value| value.kiosk_mode);
+            let window_icon_path = config
+                .as_ref()
+                .and_then(|value| value.window_icon_path.clone());
+            let window_background = config
+                .as_ref()
+                .and_then(|value| value.window_background.clone());
+            let webview_headers_script = config
+                .as_ref()
+                .filter(|value| !value.webview_headers.is_empty())
+                .map(webview_headers_script);
             let window_width = config
                 .as_ref()
                 .map(|value| value.window_width)
@@ -999,16 +7667,72 @@ fn main() {
                 .as_ref()
                 .map(|value| value.window_height)
                 .unwrap_or(DEFAULT_HEIGHT);
-            let allowed_hosts = config
-                .as_ref()
-                .map(|value| value.allowed_hosts.clone())
-                .unwrap_or_default();
-            let mut allowed_hosts_for_log: Vec<String> = allowed_hosts.iter().cloned().collect();
-            allowed_hosts_for_log.sort();
-            let allowed_hosts_for_log = allowed_hosts_for_log.join(",");
             // Use the same ICO payload as installer/exe resources so runtime taskbar icon matches.
             let app_icon = tauri::Icon::Raw(include_bytes!("../icons/icon.ico").to_vec());
             let webview_data_path = appdata_webview_data_path();
+            let saved_geometry = load_window_geometry();
+            let size_restored = saved_geometry.as_ref().is_some_and(|geometry| {
+                geometry.width >= MIN_RESTORED_WIDTH && geometry.height >= MIN_RESTORED_HEIGHT
+            });
+            let (window_width, window_height) = match &saved_geometry {
+                Some(geometry) if size_restored => (geometry.width, geometry.height),
+                _ => (window_width, window_height),
+            };
+            // Percent and physical sizes need the monitor, which is only known once the
+            // window exists; a restored size is already in logical pixels.
+            let monitor_sized = config.as_ref().filter(|value| {
+                !size_restored
+                    && (value.window_size_unit == WindowSizeUnit::Physical
+                        || value.window_width_percent.is_some()
+                        || value.window_height_percent.is_some())
+            });
+            let min_size = config
+                .as_ref()
+                .filter(|value| {
+                    value.window_min_width.is_some() || value.window_min_height.is_some()
+                })
+                .map(|value| {
+                    (
+                        value.window_min_width.unwrap_or(0.0),
+                        value.window_min_height.unwrap_or(0.0),
+                    )
+                });
+            let max_size = config
+                .as_ref()
+                .filter(|value| {
+                    value.window_max_width.is_some() || value.window_max_height.is_some()
+                })
+                .map(|value| {
+                    (
+                        value.window_max_width.unwrap_or(UNBOUNDED_WINDOW_DIMENSION),
+                        value
+                            .window_max_height
+                            .unwrap_or(UNBOUNDED_WINDOW_DIMENSION),
+                    )
+                });
+            // A size saved before the bounds were configured must still respect them.
+            let (window_width, window_height) = (
+                window_width
+                    .max(min_size.map_or(0.0, |size| size.0))
+                    .min(max_size.map_or(f64::MAX, |size| size.0)),
+                window_height
+                    .max(min_size.map_or(0.0, |size| size.1))
+                    .min(max_size.map_or(f64::MAX, |size| size.1)),
+            );
+
+            // The splash is an embedded data URL so it renders even when the server is down.
+            if let Some(url) = splash_url(&window_title, window_background.as_deref()) {
+                let splash = tauri::WindowBuilder::new(app, "splash", WindowUrl::External(url))
+                    .title(window_title.clone())
+                    .inner_size(360.0, 220.0)
+                    .resizable(false)
+                    .decorations(false)
+                    .center()
+                    .build();
+                if let Err(error) = splash {
+                    append_startup_log_entry(&format!("splash_window=error:{error}"));
+                }
+            }
 
             let mut window_builder =
                 tauri::WindowBuilder::new(app, "main", WindowUrl::App("index.html".into()))
@@ -1018,42 +7742,560 @@ fn main() {
                     .visible(false)
                     .initialization_script(INIT_SCRIPT)
                     .on_navigation(move |url| {
-                        if is_allowed_navigation(&url, &allowed_hosts) {
-                            return true;
+                        // Read the live config so profile switches take effect immediately.
+                        let state = app_handle.state::<AppState>();
+                        let config = state.snapshot().config;
+                        let allowed_hosts = config
+                            .as_ref()
+                            .map(|value| value.allowed_hosts.clone())
+                            .unwrap_or_default();
+                        let audit = |decision: &str| {
+                            if config.as_ref().is_some_and(|value| value.audit_navigation) {
+                                append_startup_log_entry(&format!(
+                                    "navigation_audit timestamp={} decision={decision} url={url}",
+                                    current_timestamp()
+                                ));
+                            }
+                        };
+                        if let Some(value) = config.as_ref().filter(|value| {
+                            is_blocked_downgrade(value.strict_https, &value.app_url, &url)
+                        }) {
+                            append_startup_log_entry(&format!(
+                                "blocked_downgrade timestamp={} url={} reason={} app_url={} referrer={}",
+                                current_timestamp(),
+                                url,
+                                NavigationBlock::Downgrade.as_str(),
+                                value.app_url,
+                                state.navigation_referrer()
+                            ));
+                            audit("blocked_downgrade");
+                            return false;
+                        }
+
+                        let https_seen = url.scheme() == "http"
+                            && !config
+                                .as_ref()
+                                .is_some_and(|value| value.allow_https_downgrade)
+                            && url
+                                .host_str()
+                                .map(normalize_host)
+                                .is_some_and(|host| state.is_https_seen_host(&host));
+                        if https_seen {
+                            append_startup_log_entry(&format!(
+                        "blocked_downgrade timestamp={} url={} reason={} cause=https_seen referrer={}",
+                        current_timestamp(),
+                        url,
+                        NavigationBlock::Downgrade.as_str(),
+                        state.navigation_referrer()
+                    ));
+                            audit("blocked_downgrade");
+                            return false;
+                        }
+
+                        let strict_allowed_hosts = config
+                            .as_ref()
+                            .is_some_and(|value| value.strict_allowed_hosts);
+                        let block_data_urls = config
+                            .as_ref()
+                            .is_some_and(|value| value.block_data_urls);
+                        let block = match is_allowed_navigation(
+                            &url,
+                            &allowed_hosts,
+                            strict_allowed_hosts,
+                            block_data_urls,
+                        ) {
+                            Ok(()) => {
+                                state.record_allowed_navigation(&url);
+                                state.record_https_host(&url);
+                                audit("allowed");
+                                return true;
+                            }
+                            Err(block) => block,
+                        };
+
+                        if config
+                            .as_ref()
+                            .is_some_and(|value| value.external_schemes.contains(url.scheme()))
+                        {
+                            hand_off_external_url(&app_handle, &url);
+                            audit("external");
+                            return false;
                         }
 
                         append_startup_log_entry(&format!(
-                            "blocked_navigation timestamp={} url={} allowed_hosts={}",
+                            "blocked_navigation timestamp={} url={} reason={} referrer={} allowed_hosts={}",
                             current_timestamp(),
                             url,
-                            allowed_hosts_for_log
+                            block.as_str(),
+                            state.navigation_referrer(),
+                            sorted_entries(&allowed_hosts).join(",")
                         ));
+                        audit("blocked");
                         false
                     });
 
+            if let Some(color) = &window_background {
+                window_builder = window_builder.initialization_script(
+                    &WINDOW_BACKGROUND_SCRIPT.replace("{{background}}", color),
+                );
+            }
+
+            if let Some(script) = &webview_headers_script {
+                window_builder = window_builder.initialization_script(script);
+            }
+
+            if kiosk_mode {
+                window_builder = window_builder
+                    .fullscreen(true)
+                    .decorations(false)
+                    .initialization_script(KIOSK_SCRIPT);
+            }
+
+            if let Some((width, height)) = min_size {
+                window_builder = window_builder.min_inner_size(width, height);
+            }
+            if let Some((width, height)) = max_size {
+                window_builder = window_builder.max_inner_size(width, height);
+            }
+
             if let Some(path) = webview_data_path {
                 fs::create_dir_all(&path)
                     .map_err(|error| -> Box<dyn std::error::Error> { Box::new(error) })?;
                 window_builder = window_builder.data_directory(path);
             }
 
-            window_builder = window_builder
-                .icon(app_icon)
-                .map_err(|error| -> Box<dyn std::error::Error> { Box::new(error) })?;
-
-            window_builder
+            let window = window_builder
                 .build()
                 .map_err(|error| -> Box<dyn std::error::Error> { Box::new(error) })?;
 
+            // Set after build so a bad rebranded icon cannot abort startup; the window is
+            // still hidden at this point, so a valid icon looks exactly as before.
+            let custom_icon_set = window_icon_path.is_some_and(|path| {
+                let applied =
+                    fs::read(&path)
+                        .map_err(|error| error.to_string())
+                        .and_then(|bytes| {
+                            window
+                                .set_icon(tauri::Icon::Raw(bytes))
+                                .map_err(|error| error.to_string())
+                        });
+                match applied {
+                    Ok(()) => {
+                        append_startup_log_entry(&format!(
+                            "window_icon_source=file:{}",
+                            path.display()
+                        ));
+                        true
+                    }
+                    Err(error) => {
+                        append_startup_log_entry(&format!(
+                            "window_icon=error:{}: {error}; falling back to embedded",
+                            path.display()
+                        ));
+                        false
+                    }
+                }
+            });
+            if !custom_icon_set {
+                append_startup_log_entry("window_icon_source=embedded");
+                if let Err(error) = window.set_icon(app_icon) {
+                    append_startup_log_entry(&format!("window_icon=error:{error}"));
+                }
+            }
+
+            if let Some(value) = monitor_sized {
+                let monitor = window
+                    .current_monitor()
+                    .ok()
+                    .flatten()
+                    .or_else(|| window.primary_monitor().ok().flatten());
+                match monitor {
+                    Some(monitor) => {
+                        let size = initial_window_size(value, &monitor);
+                        let size = LogicalSize::new(
+                            size.width
+                                .max(min_size.map_or(0.0, |bounds| bounds.0))
+                                .min(max_size.map_or(f64::MAX, |bounds| bounds.0)),
+                            size.height
+                                .max(min_size.map_or(0.0, |bounds| bounds.1))
+                                .min(max_size.map_or(f64::MAX, |bounds| bounds.1)),
+                        );
+                        append_startup_log_entry(&format!(
+                            "window_initial_size={:.0}x{:.0} scale_factor={} unit={}",
+                            size.width,
+                            size.height,
+                            monitor.scale_factor(),
+                            value.window_size_unit.as_str()
+                        ));
+                        if let Err(error) = window.set_size(size) {
+                            append_startup_log_entry(&format!("window_initial_size=error:{error}"));
+                        } else if value.window_width_percent.is_some()
+                            || value.window_height_percent.is_some()
+                        {
+                            let _ = window.center();
+                        }
+                    }
+                    None => append_startup_log_entry(
+                        "window_initial_size=no-monitor; using configured logical size",
+                    ),
+                }
+            }
+
+            if let Some(geometry) = saved_geometry {
+                restore_window_position(&window, &geometry);
+            }
+
+            // STARTUP_GRACE_SECS deliberately extends the first bootstrap, so it is not
+            // counted against the ceiling.
+            let bootstrap_deadline = config
+                .as_ref()
+                .map_or(Duration::from_secs(DEFAULT_BOOTSTRAP_MAX_SECS), |value| {
+                    value.bootstrap_max + value.startup_grace
+                });
+            tauri::async_runtime::spawn(enforce_bootstrap_deadline(
+                window.clone(),
+                bootstrap_deadline,
+            ));
+
+            restart_reachability_watchdog(&app.handle());
+
+            if single_instance {
+                if let Err(error) = start_single_instance_listener(app.handle()) {
+                    append_startup_log_entry(&format!("single_instance=error:{error}"));
+                }
+            }
+
+            if let Some(app_url) = config.as_ref().map(|value| value.app_url.clone()) {
+                tauri::async_runtime::spawn_blocking(move || resolve_app_host(&app_url));
+            }
+
+            if let Some((config, url)) = config.as_ref().and_then(|value| {
+                value
+                    .update_manifest_url
+                    .clone()
+                    .map(|url| (value.clone(), url))
+            }) {
+                tauri::async_runtime::spawn(check_for_update(app.handle(), config, url));
+            }
+
+            if let (Some(config), Some(_)) = (
+                config.filter(|value| value.precheck_allowed_hosts),
+                reachability_client,
+            ) {
+                tauri::async_runtime::spawn(precheck_allowed_hosts(config));
+            }
+
             Ok(())
         })
+        .system_tray(build_system_tray())
+        .on_system_tray_event(handle_system_tray_event)
+        .on_page_load(|window, payload| {
+            if window.label() != "main" {
+                return;
+            }
+            if let Ok(url) = Url::parse(payload.url()) {
+                save_last_session_url(&url);
+            }
+        })
+        .on_window_event(|event| {
+            let WindowEvent::CloseRequested { api, .. } = event.event() else {
+                return;
+            };
+            let window = event.window();
+            if window.label() != "main" {
+                return;
+            }
+
+            let config = window.state::<AppState>().snapshot().config;
+            // Kiosk windows can only be closed through the exit_kiosk key combo.
+            if config.as_ref().is_some_and(|config| config.kiosk_mode) {
+                api.prevent_close();
+                return;
+            }
+
+            save_window_geometry(window);
+            let minimize_to_tray = config
+                .map(|config| config.minimize_to_tray)
+                .unwrap_or(false);
+            if minimize_to_tray {
+                api.prevent_close();
+                let _ = window.hide();
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             bootstrap_state,
             launch_app,
+            resume_last_session,
             retry_connect,
+            validate_app_url,
+            is_url_allowed,
+            save_config,
+            test_connection,
+            get_dns_info,
+            get_server_info,
+            show_offline_page,
+            reload_page,
+            get_inactivity_reset_secs,
+            get_input_restrictions,
+            get_zoom,
+            zoom_in,
+            zoom_out,
+            reset_zoom,
+            reset_to_home,
+            list_allowed_hosts,
+            navigate_to_host,
             show_main_window,
-            get_about_info
+            get_about_info,
+            reload_config,
+            relaunch,
+            get_startup_diagnostics,
+            get_effective_config,
+            get_update_info,
+            copy_diagnostics,
+            export_diagnostics_bundle,
+            open_logs_folder,
+            clear_local_state,
+            capture_screenshot,
+            export_pdf,
+            exit_kiosk,
+            open_devtools,
+            list_profiles,
+            switch_profile
         ])
         .run(tauri::generate_context!())
         .expect("error while running CRA Client desktop app");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(value: &str) -> Url {
+        Url::parse(value).expect("test URL parses")
+    }
+
+    #[test]
+    fn health_check_url_resolves_relative_path_under_app_url_path() {
+        for app_url in ["https://host/cra/app", "https://host/cra/app/"] {
+            assert_eq!(
+                health_check_url(&url(app_url), "healthz").unwrap().as_str(),
+                "https://host/cra/app/healthz"
+            );
+        }
+    }
+
+    #[test]
+    fn health_check_url_resolves_absolute_path_against_origin() {
+        for app_url in ["https://host/cra/app", "https://host/cra/app/"] {
+            assert_eq!(
+                health_check_url(&url(app_url), "/healthz")
+                    .unwrap()
+                    .as_str(),
+                "https://host/healthz"
+            );
+        }
+    }
+
+    #[test]
+    fn health_check_url_drops_app_url_query_and_fragment() {
+        let app_url = url("https://host/cra/app?tenant=eu#dashboard");
+        assert_eq!(
+            health_check_url(&app_url, "healthz").unwrap().as_str(),
+            "https://host/cra/app/healthz"
+        );
+        assert_eq!(
+            health_check_url(&app_url, "/healthz").unwrap().as_str(),
+            "https://host/healthz"
+        );
+    }
+
+    #[test]
+    fn health_check_url_rejects_other_origin() {
+        assert!(health_check_url(&url("https://host/app"), "https://other/healthz").is_err());
+    }
+
+    #[test]
+    fn upgrade_to_https_is_never_a_downgrade() {
+        let app_url = url("http://host/app");
+        for strict_https in [true, false] {
+            assert!(!is_blocked_downgrade(
+                strict_https,
+                &app_url,
+                &url("https://host/next")
+            ));
+        }
+    }
+
+    #[test]
+    fn downgrade_to_http_is_blocked_only_with_strict_https() {
+        let app_url = url("https://host/app");
+        let target = url("http://host/next");
+        assert!(is_blocked_downgrade(true, &app_url, &target));
+        assert!(!is_blocked_downgrade(false, &app_url, &target));
+    }
+
+    #[test]
+    fn downgrade_to_bundled_local_host_is_allowed() {
+        assert!(!is_blocked_downgrade(
+            true,
+            &url("https://host/app"),
+            &url("http://tauri.localhost/")
+        ));
+    }
+
+    fn parse_env(content: &str) -> HashMap<String, String> {
+        let mut values = HashMap::new();
+        parse_client_env_file(content, &mut values);
+        values
+    }
+
+    #[test]
+    fn client_env_accepts_export_prefix() {
+        let values = parse_env("export APP_URL=https://host/\nexport  WINDOW_TITLE = CRA\n");
+        assert_eq!(values["APP_URL"], "https://host/");
+        assert_eq!(values["WINDOW_TITLE"], "CRA");
+        assert!(!values.contains_key("export APP_URL"));
+    }
+
+    #[test]
+    fn client_env_quoted_values_keep_equals_and_hash() {
+        let values =
+            parse_env("REACHABILITY_AUTH_HEADER=\"Bearer a=b#c\"\nWINDOW_TITLE='CRA # = test'\n");
+        assert_eq!(values["REACHABILITY_AUTH_HEADER"], "Bearer a=b#c");
+        assert_eq!(values["WINDOW_TITLE"], "CRA # = test");
+    }
+
+    #[test]
+    fn client_env_saved_values_parse_back_unchanged() {
+        let saved = [
+            "plain",
+            "CRA # kiosk",
+            "Bearer ${TOKEN}",
+            "it's ${HOME}",
+            "it's $${HOME} and $5",
+            "say \"hi\" #1",
+        ];
+        let updates = saved
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (format!("KEY_{index}"), value.to_string()))
+            .collect::<BTreeMap<_, _>>();
+        let values = parse_env(&update_client_env_content("", &updates));
+        for (key, value) in &updates {
+            assert_eq!(&values[key], value, "{key}");
+        }
+    }
+
+    #[test]
+    fn client_env_unquoted_inline_comment_is_stripped() {
+        assert_eq!(clean_env_value("1440 # widescreen"), "1440");
+        assert_eq!(clean_env_value("1440\t# widescreen"), "1440");
+    }
+
+    #[test]
+    fn client_env_hash_inside_quotes_is_kept() {
+        assert_eq!(clean_env_value("\"a # b\""), "a # b");
+        assert_eq!(clean_env_value("'a # b' # note"), "a # b");
+    }
+
+    #[test]
+    fn client_env_url_fragment_is_kept() {
+        assert_eq!(
+            clean_env_value("https://host/app#section"),
+            "https://host/app#section"
+        );
+    }
+
+    // Deliberate: only a matched pair is unquoted, so a stray quote stays visible in the
+    // value instead of being silently dropped.
+    #[test]
+    fn client_env_unmatched_quote_is_kept() {
+        assert_eq!(clean_env_value("\"abc"), "\"abc");
+        assert_eq!(clean_env_value("abc'"), "abc'");
+    }
+
+    fn allowed(entries: &[&str]) -> HashSet<String> {
+        entries
+            .iter()
+            .map(|entry| normalize_allowed_host_entry(entry).expect("valid entry"))
+            .collect()
+    }
+
+    #[test]
+    fn normalize_host_drops_ipv6_zone_id() {
+        assert_eq!(normalize_host("fe80::1%eth0"), "fe80::1");
+        assert_eq!(normalize_host("[fe80::1%25eth0]"), "fe80::1");
+    }
+
+    #[test]
+    fn normalize_host_compresses_expanded_ipv6() {
+        assert_eq!(normalize_host("0:0:0:0:0:0:0:1"), "::1");
+        assert_eq!(
+            normalize_host("[FE80:0000:0000:0000:0000:0000:0000:0001]"),
+            "fe80::1"
+        );
+        assert_eq!(normalize_host("[::1]"), normalize_host("::1"));
+    }
+
+    #[test]
+    fn bracketed_and_bare_ipv6_entries_match_each_other() {
+        for entry in ["[::1]", "::1", "0:0:0:0:0:0:0:1"] {
+            let hosts = allowed(&[entry]);
+            for host in ["[::1]", "::1"] {
+                assert!(is_host_allowed(&normalize_host(host), Some(3000), &hosts));
+            }
+            assert!(is_allowed_navigation(&url("http://[::1]:3000/"), &hosts, true, false).is_ok());
+        }
+    }
+
+    #[test]
+    fn split_host_port_handles_both_entry_forms() {
+        assert_eq!(split_host_port("host"), ("host", None));
+        assert_eq!(split_host_port("host:3000"), ("host", Some("3000")));
+        assert_eq!(split_host_port("[fe80::1]:3000"), ("fe80::1", Some("3000")));
+        assert_eq!(split_host_port("fe80::1"), ("fe80::1", None));
+    }
+
+    #[test]
+    fn host_port_entry_matches_only_that_port() {
+        let hosts = allowed(&["192.168.50.55:3000"]);
+        assert!(is_host_allowed("192.168.50.55", Some(3000), &hosts));
+        assert!(!is_host_allowed("192.168.50.55", Some(3001), &hosts));
+        assert!(!is_host_allowed("192.168.50.55", Some(443), &hosts));
+    }
+
+    #[test]
+    fn bare_host_entry_matches_any_port() {
+        let hosts = allowed(&["cra.example.com"]);
+        for port in [Some(80), Some(443), Some(3000)] {
+            assert!(is_host_allowed("cra.example.com", port, &hosts));
+        }
+    }
+
+    #[test]
+    fn ipv6_entry_with_port_matches_only_that_port() {
+        let hosts = allowed(&["[fe80::1]:3000"]);
+        assert!(is_host_allowed("fe80::1", Some(3000), &hosts));
+        assert!(!is_host_allowed("fe80::1", Some(443), &hosts));
+        assert!(is_allowed_navigation(&url("http://[fe80::1]:3000/"), &hosts, true, false).is_ok());
+        assert_eq!(
+            is_allowed_navigation(&url("https://[fe80::1]/"), &hosts, true, false),
+            Err(NavigationBlock::Host)
+        );
+    }
+
+    #[test]
+    fn data_and_blob_urls_follow_block_data_urls() {
+        let hosts = allowed(&["host"]);
+        for raw in ["data:text/html,<p>hi</p>", "blob:https://host/0b5fd3b2"] {
+            assert_eq!(
+                is_allowed_navigation(&url(raw), &hosts, false, false),
+                Ok(())
+            );
+            assert_eq!(
+                is_allowed_navigation(&url(raw), &hosts, false, true),
+                Err(NavigationBlock::Scheme)
+            );
+        }
+    }
+}
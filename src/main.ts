@@ -1,4 +1,5 @@
 import "./styles.css";
+import { listen } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/tauri";
 
 type BootstrapState = {
@@ -6,18 +7,47 @@ type BootstrapState = {
   config_error: string | null;
   app_url: string | null;
   app_host: string | null;
+  allowed_hosts?: string[];
   window_title: string;
   window_width: number;
   window_height: number;
   version: string;
   reachable: boolean;
   reachability_error: string | null;
+  reachability_latency_ms?: number | null;
+  reachability_status?: number | null;
   web_build_hash?: string | null;
   web_build_time?: string | null;
   required_web_build_hash?: string | null;
   build_parity_ok: boolean;
   build_parity_error?: string | null;
   enforce_web_build: boolean;
+  resumable?: boolean;
+  error_code?: CommandErrorCode | null;
+  maintenance?: boolean;
+  retry_after_secs?: number | null;
+  content_type_warning?: string | null;
+};
+
+type CommandErrorCode =
+  | "config_missing"
+  | "config_invalid"
+  | "unreachable"
+  | "maintenance"
+  | "build_parity"
+  | "navigation_blocked"
+  | "window";
+
+type CommandError = {
+  code: CommandErrorCode;
+  message: string;
+};
+
+type RetryProgress = {
+  attempt: number;
+  total: number;
+  next_delay_ms: number | null;
+  error: string;
 };
 
 type AboutInfo = {
@@ -25,6 +55,8 @@ type AboutInfo = {
   version: string;
   app_host: string;
   app_url: string;
+  profile?: string | null;
+  locale?: string;
   required_web_build_hash?: string | null;
   enforce_web_build: boolean;
   web_build_hash?: string | null;
@@ -32,6 +64,54 @@ type AboutInfo = {
   web_build_error?: string | null;
 };
 
+type ServerInfo = {
+  url: string;
+  status: number;
+  json: unknown;
+  text: string | null;
+};
+
+type AboutLabels = {
+  version: string;
+  serverVersion: string;
+  targetHost: string;
+  url: string;
+  profile: string;
+  webBuildHash: string;
+  webBuildTime: string;
+  requiredBuildHash: string;
+  enforceBuildParity: string;
+  buildCheckError: string;
+};
+
+// Keep in step with the locales embedded in the client; unknown locales use English.
+const ABOUT_LABELS: Record<string, AboutLabels> = {
+  en: {
+    version: "Version",
+    serverVersion: "Server Version",
+    targetHost: "Target Host",
+    url: "URL",
+    profile: "Profile",
+    webBuildHash: "Web Build Hash",
+    webBuildTime: "Web Build Time",
+    requiredBuildHash: "Required Build Hash",
+    enforceBuildParity: "Enforce Build Parity",
+    buildCheckError: "Build Check Error",
+  },
+  fr: {
+    version: "Version",
+    serverVersion: "Version du serveur",
+    targetHost: "Hôte cible",
+    url: "URL",
+    profile: "Profil",
+    webBuildHash: "Hash du build web",
+    webBuildTime: "Date du build web",
+    requiredBuildHash: "Hash de build requis",
+    enforceBuildParity: "Parité de build imposée",
+    buildCheckError: "Erreur de vérification du build",
+  },
+};
+
 const app = document.querySelector<HTMLDivElement>("#app");
 if (!app) {
   throw new Error("App root not found");
@@ -49,6 +129,7 @@ app.innerHTML = `
       <div id="actions" class="actions hidden">
         <button id="retry" type="button" disabled>Retry</button>
         <button id="about" type="button">About</button>
+        <button id="logs" type="button">Logs</button>
       </div>
     </section>
 
@@ -78,10 +159,37 @@ const details = requiredElement<HTMLParagraphElement>("#details");
 const actions = requiredElement<HTMLDivElement>("#actions");
 const retry = requiredElement<HTMLButtonElement>("#retry");
 const about = requiredElement<HTMLButtonElement>("#about");
+const logs = requiredElement<HTMLButtonElement>("#logs");
 const aboutDialog = requiredElement<HTMLDialogElement>("#aboutDialog");
 const aboutBody = requiredElement<HTMLParagraphElement>("#aboutBody");
 
 let windowVisible = false;
+let maintenanceTimer: number | null = null;
+
+function isCommandError(error: unknown): error is CommandError {
+  return typeof error === "object" && error !== null && "code" in error && "message" in error;
+}
+
+function errorMessage(error: unknown): string {
+  return isCommandError(error) ? error.message : String(error);
+}
+
+// Retrying cannot fix a bad config, so those errors leave only About and Logs enabled.
+function showCommandError(error: unknown, fallbackTitle: string): void {
+  if (isCommandError(error) && error.code.startsWith("config_")) {
+    setStatus("error", "Configuration error");
+    setErrorMode(error.message);
+    retry.disabled = true;
+    return;
+  }
+  if (isCommandError(error) && error.code === "maintenance") {
+    setStatus("warning", "Scheduled maintenance");
+    setErrorMode(error.message);
+    return;
+  }
+  setStatus("error", fallbackTitle);
+  setErrorMode(errorMessage(error));
+}
 
 function setStatus(kind: "loading" | "ok" | "warning" | "error", message: string): void {
   status.className = `status ${kind}`;
@@ -117,21 +225,75 @@ async function ensureMainWindowVisible(): Promise<void> {
   }
 }
 
+// Prefers a `version` field, then any other string/number field, then the raw body.
+function describeServerInfo(info: ServerInfo): string {
+  if (info.json && typeof info.json === "object") {
+    const record = info.json as Record<string, unknown>;
+    const version = record.version ?? record.build ?? record.commit;
+    if (typeof version === "string" || typeof version === "number") {
+      return String(version);
+    }
+    return JSON.stringify(info.json);
+  }
+  if (info.json !== null && info.json !== undefined) {
+    return String(info.json);
+  }
+  return info.text?.split("\n")[0] || "-";
+}
+
+function clearMaintenanceCountdown(): void {
+  if (maintenanceTimer !== null) {
+    window.clearInterval(maintenanceTimer);
+    maintenanceTimer = null;
+  }
+}
+
+// Counts down the server's Retry-After and re-runs bootstrap when it expires. Without a
+// delay (an HTTP-date Retry-After) only the manual Retry button is offered.
+function showMaintenance(message: string, retryAfterSecs: number | null): void {
+  clearMaintenanceCountdown();
+  setStatus("warning", "Scheduled maintenance");
+  setErrorMode(message);
+  if (retryAfterSecs === null) {
+    return;
+  }
+
+  const deadline = Date.now() + retryAfterSecs * 1000;
+  const tick = (): void => {
+    const remaining = Math.ceil((deadline - Date.now()) / 1000);
+    if (remaining <= 0) {
+      clearMaintenanceCountdown();
+      void bootstrap();
+      return;
+    }
+    const minutes = Math.floor(remaining / 60);
+    const seconds = String(remaining % 60).padStart(2, "0");
+    setDetails(`${message}\nRetrying in ${minutes}:${seconds}...`);
+  };
+  tick();
+  maintenanceTimer = window.setInterval(tick, 1000);
+}
+
 async function showAboutDialog(): Promise<void> {
   try {
     const info = await invoke<AboutInfo>("get_about_info");
+    const labels = ABOUT_LABELS[info.locale ?? "en"] ?? ABOUT_LABELS.en;
     const lines = [
       `${info.title}`,
-      `Version: ${info.version}`,
-      `Target Host: ${info.app_host}`,
-      `URL: ${info.app_url}`,
-      `Web Build Hash: ${info.web_build_hash ?? "-"}`,
-      `Web Build Time: ${info.web_build_time ?? "-"}`,
-      `Required Build Hash: ${info.required_web_build_hash ?? "-"}`,
-      `Enforce Build Parity: ${info.enforce_web_build ? "true" : "false"}`,
+      `${labels.version}: ${info.version}`,
+      `${labels.serverVersion}: ${await invoke<ServerInfo>("get_server_info")
+        .then(describeServerInfo)
+        .catch(() => "-")}`,
+      `${labels.targetHost}: ${info.app_host}`,
+      `${labels.url}: ${info.app_url}`,
+      `${labels.profile}: ${info.profile ?? "-"}`,
+      `${labels.webBuildHash}: ${info.web_build_hash ?? "-"}`,
+      `${labels.webBuildTime}: ${info.web_build_time ?? "-"}`,
+      `${labels.requiredBuildHash}: ${info.required_web_build_hash ?? "-"}`,
+      `${labels.enforceBuildParity}: ${info.enforce_web_build ? "true" : "false"}`,
     ];
     if (info.web_build_error) {
-      lines.push(`Build Check Error: ${info.web_build_error}`);
+      lines.push(`${labels.buildCheckError}: ${info.web_build_error}`);
     }
     aboutBody.textContent = lines.join("\n");
   } catch (error) {
@@ -148,21 +310,29 @@ async function openRemoteApp(): Promise<void> {
     await invoke("launch_app");
   } catch (error) {
     await ensureMainWindowVisible();
-    setStatus("error", "Could not open the app.");
-    setErrorMode(String(error));
+    showCommandError(error, "Could not open the app.");
   }
 }
 
 async function retryConnection(): Promise<void> {
+  clearMaintenanceCountdown();
   setStatus("loading", "Retrying connection...");
   setLoaderMode();
 
+  const unlisten = await listen<RetryProgress>("retry-progress", ({ payload }) => {
+    if (payload.next_delay_ms !== null) {
+      const seconds = Math.ceil(payload.next_delay_ms / 1000);
+      setStatus("loading", `Retry ${payload.attempt} of ${payload.total}, next attempt in ${seconds}s...`);
+    }
+  }).catch(() => null);
+
   try {
     await invoke("retry_connect");
   } catch (error) {
     await ensureMainWindowVisible();
-    setStatus("error", "Server is still unreachable.");
-    setErrorMode(String(error));
+    showCommandError(error, "Server is still unreachable.");
+  } finally {
+    unlisten?.();
   }
 }
 
@@ -184,6 +354,12 @@ async function bootstrap(): Promise<void> {
     }
 
     if (state.reachable) {
+      if (state.content_type_warning) {
+        setStatus("warning", "Unexpected server response");
+        setDetails(state.content_type_warning);
+        details.classList.remove("hidden");
+      }
+
       if (!state.build_parity_ok) {
         const message =
           state.build_parity_error ??
@@ -207,12 +383,24 @@ async function bootstrap(): Promise<void> {
     }
 
     await ensureMainWindowVisible();
+    if (state.maintenance) {
+      showMaintenance(
+        state.reachability_error ?? "The server is down for scheduled maintenance.",
+        state.retry_after_secs ?? null,
+      );
+      return;
+    }
+
     setStatus("error", "Server unreachable");
     setErrorMode(state.reachability_error ?? "The server did not respond.");
+    try {
+      await invoke("show_offline_page", { error: state.reachability_error ?? null });
+    } catch {
+      // The inline error view above stays usable if the offline page cannot be shown.
+    }
   } catch (error) {
     await ensureMainWindowVisible();
-    setStatus("error", "Bootstrap failed");
-    setErrorMode(String(error));
+    showCommandError(error, "Bootstrap failed");
   }
 }
 
@@ -224,4 +412,10 @@ about.addEventListener("click", () => {
   void showAboutDialog();
 });
 
+logs.addEventListener("click", () => {
+  void invoke("open_logs_folder").catch((error) => {
+    setDetails(`Could not open logs folder: ${String(error)}`);
+  });
+});
+
 void bootstrap();